@@ -0,0 +1,6 @@
+// Encoder and decoder of 0.1.4, copied as they are, so the numbers are compared against the real old code
+#![allow(clippy::all, dead_code)]
+
+pub mod decode_from_url_string;
+pub mod encode_map;
+pub mod url_encoder;
@@ -0,0 +1,76 @@
+mod baseline;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use url_utils::url_encoder::EncodeSet;
+
+// Mostly safe text, the typical case for query values
+const PLAIN: &str = "tableName=deposit-restrictions&partitionKey=abc123&rowKey=1abfc&field=value";
+const MIXED: &str = "name=John Doe&email=john.doe@example.com&redirect=https://host/path?a=1&b=2";
+const UNICODE: &str = "city=Київ&street=Хрещатик 22&comment=café, crème brûlée";
+
+fn bench_encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+
+    // Long runs of safe bytes is where the fast skip pays off
+    let long = PLAIN.replace(['=', '&'], "-").repeat(50);
+
+    for (name, src) in [
+        ("plain", PLAIN),
+        ("mixed", MIXED),
+        ("unicode", UNICODE),
+        ("long", long.as_str()),
+    ] {
+        group.bench_with_input(BenchmarkId::new("baseline", name), src, |b, src| {
+            b.iter(|| baseline::url_encoder::encode_string(black_box(src)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("table", name), src, |b, src| {
+            b.iter(|| {
+                url_utils::url_encoder::encode_with(EncodeSet::FormUrlEncoded, black_box(src))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+
+    // The old decoder panics on escapes outside of its maps and breaks non-ASCII text,
+    // so both of them get the input encoded by the old encoder
+    for (name, src) in [("plain", PLAIN), ("mixed", MIXED)] {
+        let encoded = baseline::url_encoder::encode_string(src)
+            .as_str()
+            .to_string();
+
+        group.bench_with_input(
+            BenchmarkId::new("baseline", name),
+            encoded.as_str(),
+            |b, src| {
+                b.iter(|| baseline::decode_from_url_string::decode_from_url_string(black_box(src)))
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("fast_skip", name),
+            encoded.as_str(),
+            |b, src| b.iter(|| url_utils::url_decoder::decode_to_bytes(black_box(src)).unwrap()),
+        );
+    }
+
+    let encoded = url_utils::url_encoder::encode_string(UNICODE)
+        .as_str()
+        .to_string();
+
+    group.bench_with_input(
+        BenchmarkId::new("fast_skip", "unicode"),
+        encoded.as_str(),
+        |b, src| b.iter(|| url_utils::url_decoder::decode_to_bytes(black_box(src)).unwrap()),
+    );
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_encode, bench_decode);
+criterion_main!(benches);
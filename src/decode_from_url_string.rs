@@ -1,143 +1,43 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
 
-use lazy_static::lazy_static;
 use rust_extensions::StrOrString;
 
-pub fn decode_from_url_string<'s>(src: &'s str) -> StrOrString<'s> {
-    let index = src.find("%");
+use crate::url_decoder::UrlDecodeError;
 
-    if index.is_none() {
-        return StrOrString::create_as_str(src);
+// Never fails: broken escapes are kept as they are and invalid UTF-8 becomes U+FFFD.
+// Use try_decode_from_url_string to reject such input
+pub fn decode_from_url_string(src: &str) -> StrOrString<'_> {
+    match crate::url_decoder::decode_lossy(src) {
+        Cow::Borrowed(result) => StrOrString::create_as_str(result),
+        Cow::Owned(result) => StrOrString::create_as_string(result),
     }
+}
 
-    let mut result: Vec<u8> = Vec::new();
-
-    let mut is_escape_symbol_mode = false;
-    let mut escape_pos: u32 = 0;
-    let mut escape0: u8 = 0;
-
-    for (_, c) in src.chars().enumerate() {
-        if is_escape_symbol_mode {
-            if escape_pos == 0 {
-                escape0 = c as u8;
-                escape_pos += 1;
-            } else if escape_pos == 1 {
-                escape_pos += 1;
-                let c = decode_url_escape(escape0, c as u8);
-                result.push(c);
-                is_escape_symbol_mode = false;
-            }
-        } else {
-            if c != '%' {
-                if c == '+' {
-                    result.push(' ' as u8);
-                } else {
-                    result.push(c as u8);
-                }
-            } else {
-                is_escape_symbol_mode = true;
-                escape_pos = 0;
-            }
-        }
-    }
+pub fn try_decode_from_url_string(src: &str) -> Result<StrOrString<'_>, UrlDecodeError> {
+    crate::url_decoder::decode_as_str_or_string(src)
+}
 
-    return StrOrString::create_as_string(String::from_utf8(result).unwrap());
+// Any hex pair is accepted: `%7C` as well as `%2F`. None if one of the symbols is not a hex digit
+pub fn try_decode_url_escape(s0: u8, s1: u8) -> Option<u8> {
+    let high = crate::url_decoder::decode_hex_digit(s0)?;
+    let low = crate::url_decoder::decode_hex_digit(s1)?;
+    Some(high * 16 + low)
 }
 
+#[deprecated(note = "Panics on input which is not a hex pair. Use try_decode_url_escape")]
 pub fn decode_url_escape(s0: u8, s1: u8) -> u8 {
-    if s0 == b'2' {
-        return URL_DECODE_SYMBOLS_2.get(&s1).unwrap().clone();
-    }
-
-    if s0 == b'3' {
-        return URL_DECODE_SYMBOLS_3.get(&s1).unwrap().clone();
-    }
-
-    if s0 == b'4' && s1 == b'0' {
-        return b'@';
-    }
-
-    if s0 == b'5' {
-        if s1 == b'B' || s1 == b'b' {
-            return b'[';
-        }
-        if s1 == b'D' || s1 == b'D' {
-            return b']';
-        }
+    match try_decode_url_escape(s0, s1) {
+        Some(result) => result,
+        None => panic!("Invalid URL Symbol %{}{}", s0 as char, s1 as char),
     }
-
-    panic!("Invalid URL Symbol %{}{}", s0 as char, s1 as char);
 }
 
 pub fn encode_to_url_string_and_copy(res: &mut String, src: &str) {
-    use crate::url_encoder::encode_map::URL_ENCODE_SYMBOLS;
-    let mut has_symbol_to_encode = false;
-    for (_, c) in src.chars().enumerate() {
-        if URL_ENCODE_SYMBOLS.contains_key(&c) {
-            has_symbol_to_encode = true;
-            break;
-        }
-    }
-
-    if !has_symbol_to_encode {
-        res.push_str(src);
-        return;
-    }
-
-    for (_, c) in src.chars().enumerate() {
-        let found = URL_ENCODE_SYMBOLS.get(&c);
-
-        match found {
-            Some(str) => {
-                res.push_str(str);
-            }
-            None => {
-                res.push(c);
-            }
-        }
-    }
-}
-
-lazy_static! {
-    static ref URL_DECODE_SYMBOLS_2: HashMap<u8, u8> = [
-        (b'3', b'#'),
-        (b'4', b'$'),
-        (b'5', b'%'),
-        (b'6', b'&'),
-        (b'7', b'\''),
-        (b'8', b'('),
-        (b'9', b')'),
-        (b'A', b'*'),
-        (b'a', b'*'),
-        (b'B', b'+'),
-        (b'b', b'+'),
-        (b'C', b','),
-        (b'c', b','),
-        (b'F', b'/'),
-        (b'f', b'/'),
-    ]
-    .iter()
-    .copied()
-    .collect();
-}
-
-lazy_static! {
-    static ref URL_DECODE_SYMBOLS_3: HashMap<u8, u8> = [
-        (b'A', b':'),
-        (b'a', b':'),
-        (b'B', b';'),
-        (b'b', b';'),
-        (b'D', b'='),
-        (b'd', b'='),
-        (b'F', b'?'),
-        (b'f', b'?'),
-//        ('@', "%40"),
-//        ('[', "%5B"),
-//        (']', "%5D"),
-    ]
-    .iter()
-    .copied()
-    .collect();
+    crate::url_encoder::encode_with_and_copy(
+        res,
+        crate::url_encoder::EncodeSet::FormUrlEncoded,
+        src,
+    );
 }
 
 #[cfg(test)]
@@ -154,4 +54,43 @@ mod tests {
             "4/0AeaYSHA_pv6LYFSy9QdDASiSdr4X53iOaoo9ZJotKi536ELdyaLNqbsaQ0sjsTE9yuhhdQ"
         );
     }
+
+    #[test]
+    fn test_decode_url_escape() {
+        assert_eq!(super::try_decode_url_escape(b'2', b'F'), Some(b'/'));
+        assert_eq!(super::try_decode_url_escape(b'7', b'c'), Some(b'|'));
+        assert_eq!(super::try_decode_url_escape(b'C', b'3'), Some(0xC3));
+        assert_eq!(super::try_decode_url_escape(b'z', b'z'), None);
+        assert_eq!(super::try_decode_url_escape(b'2', b'%'), None);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_decode_url_escape() {
+        assert_eq!(super::decode_url_escape(b'2', b'F'), b'/');
+
+        let result = std::panic::catch_unwind(|| super::decode_url_escape(b'z', b'z'));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_untrusted_input_does_not_panic() {
+        for (src, expected) in [
+            ("%7c%7C", "||"),
+            ("a+b%20c", "a b c"),
+            ("100%", "100%"),
+            ("%zz", "%zz"),
+            ("%C3%28", "\u{FFFD}("),
+            ("Київ%2F", "Київ/"),
+        ] {
+            assert_eq!(super::decode_from_url_string(src).as_str(), expected);
+        }
+
+        assert_eq!(
+            super::try_decode_from_url_string("%7c").unwrap().as_str(),
+            "|"
+        );
+        assert!(super::try_decode_from_url_string("100%").is_err());
+        assert!(super::try_decode_from_url_string("%C3%28").is_err());
+    }
 }
@@ -1,2 +1,6 @@
 mod parse_query_string;
 pub use parse_query_string::*;
+mod query_pairs;
+pub use query_pairs::*;
+mod query_serializer;
+pub use query_serializer::*;
@@ -1,18 +1,128 @@
-use crate::{url_decoder::UrlDecodeError, url_encoded_data_reader::UrlEncodedValue};
+use crate::{
+    url_decoder::{DecodeOptions, UrlDecodeError},
+    url_encoded_data_reader::{ReadingEncodedDataError, UrlEncodedValue},
+};
 
+// Elements without '=' are kept as flags: `?verbose&dry_run`. Empty elements are skipped
 pub fn parse<'s>(query_string: &'s str) -> Result<Vec<UrlEncodedValue<'s>>, UrlDecodeError> {
+    parse_with_options(query_string, DecodeOptions::default())
+}
+
+pub fn parse_with_options(
+    query_string: &str,
+    options: DecodeOptions,
+) -> Result<Vec<UrlEncodedValue<'_>>, UrlDecodeError> {
     let mut result = Vec::new();
-    let elements = query_string.split("&");
+    let elements = query_string.split(|c| options.separator.is_separator(c));
 
     for el in elements {
-        let kv = el.find('=');
+        if el.is_empty() {
+            continue;
+        }
+
+        result.push(parse_element(el, options)?);
+    }
+
+    Ok(result)
+}
+
+// Same as parse, but empty keys (`=value`, `a=1&&b=2`, trailing '&') are rejected
+pub fn parse_strict<'s>(
+    query_string: &'s str,
+) -> Result<Vec<UrlEncodedValue<'s>>, ReadingEncodedDataError> {
+    parse_strict_with_options(query_string, DecodeOptions::default())
+}
 
-        if let Some(index) = kv {
-            let key = crate::url_decoder::decode_from_url_query_string(&el[..index])?;
-            let value = UrlEncodedValue::new(key, &el[index + 1..]);
-            result.push(value);
+pub fn parse_strict_with_options(
+    query_string: &str,
+    options: DecodeOptions,
+) -> Result<Vec<UrlEncodedValue<'_>>, ReadingEncodedDataError> {
+    let mut result = Vec::new();
+
+    if query_string.is_empty() {
+        return Ok(result);
+    }
+
+    let mut pos = 0;
+
+    for el in query_string.split(|c| options.separator.is_separator(c)) {
+        if el.is_empty() || el.starts_with('=') {
+            return Err(ReadingEncodedDataError::EmptyKey { pos });
         }
+
+        result.push(parse_element(el, options)?);
+        pos += el.len() + 1;
     }
 
     Ok(result)
 }
+
+fn parse_element(el: &str, options: DecodeOptions) -> Result<UrlEncodedValue<'_>, UrlDecodeError> {
+    let (key, value) = match el.find('=') {
+        Some(index) => (&el[..index], Some(&el[index + 1..])),
+        None => (el, None),
+    };
+
+    let key = crate::url_decoder::decode_from_url_query_string_with_options(key, options)?;
+    Ok(UrlEncodedValue::new_with_options(key, value, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::url_encoded_data_reader::ReadingEncodedDataError;
+
+    #[test]
+    fn test_flags_are_kept() {
+        let result = super::parse("verbose&page=2&&dry_run&q=").unwrap();
+
+        let result: Vec<(&str, Option<&str>)> = result
+            .iter()
+            .map(|itm| (itm.get_name(), itm.value))
+            .collect();
+
+        assert_eq!(
+            result,
+            vec![
+                ("verbose", None),
+                ("page", Some("2")),
+                ("dry_run", None),
+                ("q", Some(""))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        assert_eq!(super::parse_strict("").unwrap().len(), 0);
+        assert_eq!(super::parse_strict("a=1&verbose").unwrap().len(), 2);
+
+        for (query, expected_pos) in [("a=1&&b=2", 4), ("=1", 0), ("a=1&", 4), ("a&=2", 2)] {
+            match super::parse_strict(query) {
+                Err(ReadingEncodedDataError::EmptyKey { pos }) => assert_eq!(pos, expected_pos),
+                _ => panic!("Query '{}' must fail", query),
+            }
+        }
+    }
+
+    #[test]
+    fn test_separators() {
+        use crate::url_decoder::{DecodeOptions, PairSeparator};
+
+        let options = DecodeOptions::default().with_separator(PairSeparator::Semicolon);
+        let result = super::parse_with_options("a=1;b=2&c=3", options).unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1].value, Some("2&c=3"));
+
+        let options = DecodeOptions::default().with_separator(PairSeparator::Both);
+        let result = super::parse_with_options("a=1;b=2&c=3", options).unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[2].get_name(), "c");
+
+        match super::parse_strict_with_options("a=1;;b=2", options) {
+            Err(ReadingEncodedDataError::EmptyKey { pos }) => assert_eq!(pos, 4),
+            _ => panic!("Empty element must fail"),
+        }
+    }
+}
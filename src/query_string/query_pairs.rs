@@ -0,0 +1,114 @@
+use std::borrow::Cow;
+
+// Splits and decodes lazily. Keys and values without '%' and '+' are borrowed from the source.
+// Pieces with invalid escapes are yielded as they are
+pub struct QueryPairs<'s> {
+    rest: &'s str,
+}
+
+impl<'s> QueryPairs<'s> {
+    pub fn new(query: &'s str) -> Self {
+        Self {
+            rest: query.strip_prefix('?').unwrap_or(query),
+        }
+    }
+
+    // Stops scanning at the first match, so the rest of the query is not touched
+    pub fn find(&mut self, name: &str) -> Option<(Cow<'s, str>, Option<Cow<'s, str>>)> {
+        while let Some(el) = self.next_element() {
+            let (key, value) = split_element(el);
+            let key = decode(key);
+
+            if key == name {
+                return Some((key, value.map(decode)));
+            }
+        }
+
+        None
+    }
+
+    fn next_element(&mut self) -> Option<&'s str> {
+        while !self.rest.is_empty() {
+            let el = match self.rest.find('&') {
+                Some(index) => {
+                    let el = &self.rest[..index];
+                    self.rest = &self.rest[index + 1..];
+                    el
+                }
+                None => std::mem::take(&mut self.rest),
+            };
+
+            if !el.is_empty() {
+                return Some(el);
+            }
+        }
+
+        None
+    }
+}
+
+impl<'s> Iterator for QueryPairs<'s> {
+    type Item = (Cow<'s, str>, Option<Cow<'s, str>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = split_element(self.next_element()?);
+        Some((decode(key), value.map(decode)))
+    }
+}
+
+fn split_element(el: &str) -> (&str, Option<&str>) {
+    match el.find('=') {
+        Some(index) => (&el[..index], Some(&el[index + 1..])),
+        None => (el, None),
+    }
+}
+
+fn decode(src: &str) -> Cow<'_, str> {
+    if !src.contains(['%', '+']) {
+        return Cow::Borrowed(src);
+    }
+
+    match crate::url_decoder::decode_from_url_query_string(src) {
+        Ok(result) => Cow::Owned(result),
+        Err(_) => Cow::Borrowed(src),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::QueryPairs;
+
+    #[test]
+    fn test_pairs() {
+        let pairs: Vec<_> = QueryPairs::new("?a=1&&name=John+Doe&verbose&e%3D=").collect();
+
+        assert_eq!(
+            pairs,
+            vec![
+                (Cow::Borrowed("a"), Some(Cow::Borrowed("1"))),
+                (Cow::Borrowed("name"), Some(Cow::Borrowed("John Doe"))),
+                (Cow::Borrowed("verbose"), None),
+                (Cow::Borrowed("e="), Some(Cow::Borrowed(""))),
+            ]
+        );
+
+        assert!(matches!(pairs[0].0, Cow::Borrowed(_)));
+        assert!(matches!(pairs[1].1, Some(Cow::Owned(_))));
+    }
+
+    #[test]
+    fn test_find() {
+        let mut pairs = QueryPairs::new("a=1&b=2&a=3&c");
+
+        let (_, value) = pairs.find("a").unwrap();
+        assert_eq!(value.unwrap(), "1");
+
+        let (_, value) = pairs.find("a").unwrap();
+        assert_eq!(value.unwrap(), "3");
+
+        assert_eq!(pairs.find("c").unwrap().1, None);
+        assert!(pairs.find("a").is_none());
+    }
+}
@@ -0,0 +1,912 @@
+use std::fmt::Display;
+
+use serde::ser::{
+    Impossible, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple,
+    SerializeTupleStruct, Serializer,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum QuerySequenceFormat {
+    // tags=a&tags=b
+    #[default]
+    RepeatedKey,
+    // tags[]=a&tags[]=b
+    Brackets,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuerySerializeError {
+    TopLevelMustBeStructOrMap,
+    UnsupportedValue {
+        key: String,
+        value_type: &'static str,
+    },
+    UnsupportedKey(&'static str),
+    Custom(String),
+}
+
+impl Display for QuerySerializeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TopLevelMustBeStructOrMap => {
+                write!(
+                    f,
+                    "Only structs and maps can be serialized into query params"
+                )
+            }
+            Self::UnsupportedValue { key, value_type } => {
+                write!(f, "Can not serialize {} as value of '{}'", value_type, key)
+            }
+            Self::UnsupportedKey(key_type) => {
+                write!(f, "Can not serialize {} as query param name", key_type)
+            }
+            Self::Custom(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for QuerySerializeError {}
+
+impl serde::ser::Error for QuerySerializeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// Flattens struct or map into the list of not encoded query params.
+/// Nested structs and maps are written as `parent[child]`
+pub fn to_query_params<T: Serialize + ?Sized>(
+    value: &T,
+    format: QuerySequenceFormat,
+) -> Result<Vec<(String, String)>, QuerySerializeError> {
+    let mut params = Vec::new();
+
+    value.serialize(QuerySerializer {
+        params: &mut params,
+        format,
+    })?;
+
+    Ok(params)
+}
+
+struct QuerySerializer<'a> {
+    params: &'a mut Vec<(String, String)>,
+    format: QuerySequenceFormat,
+}
+
+impl<'a> Serializer for QuerySerializer<'a> {
+    type Ok = ();
+    type Error = QuerySerializeError;
+    type SerializeSeq = Impossible<(), QuerySerializeError>;
+    type SerializeTuple = Impossible<(), QuerySerializeError>;
+    type SerializeTupleStruct = Impossible<(), QuerySerializeError>;
+    type SerializeTupleVariant = Impossible<(), QuerySerializeError>;
+    type SerializeMap = QueryStructSerializer<'a>;
+    type SerializeStruct = QueryStructSerializer<'a>;
+    type SerializeStructVariant = Impossible<(), QuerySerializeError>;
+
+    fn serialize_bool(self, _: bool) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_i16(self, _: i16) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_i32(self, _: i32) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_i64(self, _: i64) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_u8(self, _: u8) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_u16(self, _: u16) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_u32(self, _: u32) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_u64(self, _: u64) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_char(self, _: char) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_str(self, _: &str) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_none(self) -> Result<(), QuerySerializeError> {
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), QuerySerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), QuerySerializeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), QuerySerializeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<(), QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), QuerySerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), QuerySerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, QuerySerializeError> {
+        Ok(QueryStructSerializer::new(None, self.params, self.format))
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, QuerySerializeError> {
+        Ok(QueryStructSerializer::new(None, self.params, self.format))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, QuerySerializeError> {
+        Err(QuerySerializeError::TopLevelMustBeStructOrMap)
+    }
+}
+
+struct QueryStructSerializer<'a> {
+    prefix: Option<String>,
+    params: &'a mut Vec<(String, String)>,
+    format: QuerySequenceFormat,
+    pending_key: Option<String>,
+}
+
+impl<'a> QueryStructSerializer<'a> {
+    fn new(
+        prefix: Option<String>,
+        params: &'a mut Vec<(String, String)>,
+        format: QuerySequenceFormat,
+    ) -> Self {
+        Self {
+            prefix,
+            params,
+            format,
+            pending_key: None,
+        }
+    }
+
+    fn serialize_entry_value<T: ?Sized + Serialize>(
+        &mut self,
+        key: &str,
+        value: &T,
+    ) -> Result<(), QuerySerializeError> {
+        let key = match &self.prefix {
+            Some(prefix) => format!("{}[{}]", prefix, key),
+            None => key.to_string(),
+        };
+
+        value.serialize(QueryValueSerializer {
+            key,
+            params: self.params,
+            format: self.format,
+            inside_of_seq: false,
+        })
+    }
+}
+
+impl<'a> SerializeStruct for QueryStructSerializer<'a> {
+    type Ok = ();
+    type Error = QuerySerializeError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.serialize_entry_value(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a> SerializeMap for QueryStructSerializer<'a> {
+    type Ok = ();
+    type Error = QuerySerializeError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.pending_key = Some(key.serialize(QueryKeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self
+            .pending_key
+            .take()
+            .ok_or_else(|| QuerySerializeError::Custom("Map value without a key".to_string()))?;
+
+        self.serialize_entry_value(&key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+struct QueryValueSerializer<'a> {
+    key: String,
+    params: &'a mut Vec<(String, String)>,
+    format: QuerySequenceFormat,
+    inside_of_seq: bool,
+}
+
+impl<'a> QueryValueSerializer<'a> {
+    fn push(self, value: impl Display) -> Result<(), QuerySerializeError> {
+        self.params.push((self.key, value.to_string()));
+        Ok(())
+    }
+
+    fn unsupported(self, value_type: &'static str) -> QuerySerializeError {
+        QuerySerializeError::UnsupportedValue {
+            key: self.key,
+            value_type,
+        }
+    }
+
+    fn into_seq(self) -> Result<QuerySeqSerializer<'a>, QuerySerializeError> {
+        if self.inside_of_seq {
+            return Err(self.unsupported("nested sequence"));
+        }
+
+        let key = match self.format {
+            QuerySequenceFormat::RepeatedKey => self.key,
+            QuerySequenceFormat::Brackets => format!("{}[]", self.key),
+        };
+
+        Ok(QuerySeqSerializer {
+            key,
+            params: self.params,
+            format: self.format,
+        })
+    }
+
+    fn into_struct(self) -> Result<QueryStructSerializer<'a>, QuerySerializeError> {
+        if self.inside_of_seq {
+            return Err(self.unsupported("struct or map inside of sequence"));
+        }
+
+        Ok(QueryStructSerializer::new(
+            Some(self.key),
+            self.params,
+            self.format,
+        ))
+    }
+}
+
+impl<'a> Serializer for QueryValueSerializer<'a> {
+    type Ok = ();
+    type Error = QuerySerializeError;
+    type SerializeSeq = QuerySeqSerializer<'a>;
+    type SerializeTuple = QuerySeqSerializer<'a>;
+    type SerializeTupleStruct = QuerySeqSerializer<'a>;
+    type SerializeTupleVariant = Impossible<(), QuerySerializeError>;
+    type SerializeMap = QueryStructSerializer<'a>;
+    type SerializeStruct = QueryStructSerializer<'a>;
+    type SerializeStructVariant = Impossible<(), QuerySerializeError>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), QuerySerializeError> {
+        self.push(v)
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), QuerySerializeError> {
+        Err(self.unsupported("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<(), QuerySerializeError> {
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), QuerySerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), QuerySerializeError> {
+        self.push("")
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), QuerySerializeError> {
+        self.push("")
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<(), QuerySerializeError> {
+        self.push(variant)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), QuerySerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), QuerySerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(self.unsupported("enum variant with data"))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, QuerySerializeError> {
+        self.into_seq()
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, QuerySerializeError> {
+        self.into_seq()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, QuerySerializeError> {
+        self.into_seq()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, QuerySerializeError> {
+        Err(self.unsupported("enum variant with data"))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, QuerySerializeError> {
+        self.into_struct()
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, QuerySerializeError> {
+        self.into_struct()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, QuerySerializeError> {
+        Err(self.unsupported("enum variant with data"))
+    }
+}
+
+struct QuerySeqSerializer<'a> {
+    key: String,
+    params: &'a mut Vec<(String, String)>,
+    format: QuerySequenceFormat,
+}
+
+impl<'a> QuerySeqSerializer<'a> {
+    fn serialize_item<T: ?Sized + Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), QuerySerializeError> {
+        value.serialize(QueryValueSerializer {
+            key: self.key.clone(),
+            params: self.params,
+            format: self.format,
+            inside_of_seq: true,
+        })
+    }
+}
+
+impl<'a> SerializeSeq for QuerySeqSerializer<'a> {
+    type Ok = ();
+    type Error = QuerySerializeError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a> SerializeTuple for QuerySeqSerializer<'a> {
+    type Ok = ();
+    type Error = QuerySerializeError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a> SerializeTupleStruct for QuerySeqSerializer<'a> {
+    type Ok = ();
+    type Error = QuerySerializeError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+// Map keys become param names, so only plain values are accepted
+struct QueryKeySerializer;
+
+impl Serializer for QueryKeySerializer {
+    type Ok = String;
+    type Error = QuerySerializeError;
+    type SerializeSeq = Impossible<String, QuerySerializeError>;
+    type SerializeTuple = Impossible<String, QuerySerializeError>;
+    type SerializeTupleStruct = Impossible<String, QuerySerializeError>;
+    type SerializeTupleVariant = Impossible<String, QuerySerializeError>;
+    type SerializeMap = Impossible<String, QuerySerializeError>;
+    type SerializeStruct = Impossible<String, QuerySerializeError>;
+    type SerializeStructVariant = Impossible<String, QuerySerializeError>;
+
+    fn serialize_bool(self, v: bool) -> Result<String, QuerySerializeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, QuerySerializeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, QuerySerializeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, QuerySerializeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, QuerySerializeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, QuerySerializeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, QuerySerializeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, QuerySerializeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, QuerySerializeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<String, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey("f32"))
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<String, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey("f64"))
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, QuerySerializeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, QuerySerializeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<String, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<String, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey("none"))
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<String, QuerySerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey("unit"))
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<String, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey("unit struct"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<String, QuerySerializeError> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<String, QuerySerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<String, QuerySerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(QuerySerializeError::UnsupportedKey(
+            "enum variant with data",
+        ))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey("sequence"))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey("tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey("tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey(
+            "enum variant with data",
+        ))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey("map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey("struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, QuerySerializeError> {
+        Err(QuerySerializeError::UnsupportedKey(
+            "enum variant with data",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::Serialize;
+
+    use super::{QuerySequenceFormat, QuerySerializeError};
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Status {
+        Open,
+        Closed,
+    }
+
+    #[derive(Serialize)]
+    struct Filter {
+        status: Status,
+        owner: Option<String>,
+    }
+
+    #[derive(Serialize)]
+    struct Request {
+        page: u32,
+        ratio: f64,
+        active: bool,
+        search: Option<String>,
+        tags: Vec<String>,
+        filter: Filter,
+    }
+
+    fn create_request() -> Request {
+        Request {
+            page: 2,
+            ratio: 0.5,
+            active: true,
+            search: None,
+            tags: vec!["a".to_string(), "b c".to_string()],
+            filter: Filter {
+                status: Status::Open,
+                owner: Some("me".to_string()),
+            },
+        }
+    }
+
+    #[test]
+    fn test_struct_with_repeated_keys() {
+        let params =
+            super::to_query_params(&create_request(), QuerySequenceFormat::RepeatedKey).unwrap();
+
+        let expected = vec![
+            ("page", "2"),
+            ("ratio", "0.5"),
+            ("active", "true"),
+            ("tags", "a"),
+            ("tags", "b c"),
+            ("filter[status]", "open"),
+            ("filter[owner]", "me"),
+        ];
+
+        assert_eq!(params.len(), expected.len());
+
+        for ((key, value), (expected_key, expected_value)) in params.iter().zip(expected) {
+            assert_eq!(key, expected_key);
+            assert_eq!(value, expected_value);
+        }
+    }
+
+    #[test]
+    fn test_struct_with_brackets() {
+        let params =
+            super::to_query_params(&create_request(), QuerySequenceFormat::Brackets).unwrap();
+
+        let tags: Vec<&str> = params
+            .iter()
+            .filter(|(key, _)| key == "tags[]")
+            .map(|(_, value)| value.as_str())
+            .collect();
+
+        assert_eq!(tags, vec!["a", "b c"]);
+    }
+
+    #[test]
+    fn test_map() {
+        let mut map = BTreeMap::new();
+        map.insert("b", Status::Closed);
+        map.insert("a", Status::Open);
+
+        let params = super::to_query_params(&map, QuerySequenceFormat::RepeatedKey).unwrap();
+
+        assert_eq!(
+            params,
+            vec![
+                ("a".to_string(), "open".to_string()),
+                ("b".to_string(), "closed".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_values() {
+        let result = super::to_query_params(&5, QuerySequenceFormat::RepeatedKey);
+        assert_eq!(
+            result.unwrap_err(),
+            QuerySerializeError::TopLevelMustBeStructOrMap
+        );
+
+        let mut map = BTreeMap::new();
+        map.insert("items", vec![vec![1, 2]]);
+
+        let result = super::to_query_params(&map, QuerySequenceFormat::RepeatedKey);
+        assert_eq!(
+            result.unwrap_err(),
+            QuerySerializeError::UnsupportedValue {
+                key: "items".to_string(),
+                value_type: "nested sequence"
+            }
+        );
+    }
+}
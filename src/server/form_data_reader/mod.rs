@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 mod content_disposition_parser;
 mod content_iterator;
 mod form_data_item;
@@ -11,3 +13,16 @@ pub enum ReadingFromDataError {
     ParameterMissing(String),
     ValidationError { field: String, error: String },
 }
+
+impl Display for ReadingFromDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ParameterMissing(name) => write!(f, "Parameter '{}' is missing", name),
+            Self::ValidationError { field, error } => {
+                write!(f, "Invalid value of field '{}'. {}", field, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReadingFromDataError {}
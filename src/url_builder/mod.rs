@@ -4,3 +4,11 @@ mod url_builder_inner;
 pub use url_builder_inner::*;
 mod url_builder_unix_socket;
 pub use url_builder_unix_socket::*;
+mod url_parse_error;
+pub use url_parse_error::*;
+mod normalize;
+pub use normalize::*;
+mod url_reference;
+mod user_info;
+mod utils;
+mod validation;
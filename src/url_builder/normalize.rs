@@ -0,0 +1,132 @@
+// RFC 3986 section 6 normalizations. Each one can be switched on and off separately
+#[derive(Debug, Clone, Copy)]
+pub struct UrlNormalizeOptions {
+    pub lowercase_scheme_and_host: bool,
+    pub remove_default_port: bool,
+    pub remove_dot_segments: bool,
+    pub uppercase_percent_encoding: bool,
+    pub decode_unreserved: bool,
+    pub sort_query: bool,
+}
+
+impl Default for UrlNormalizeOptions {
+    fn default() -> Self {
+        Self {
+            lowercase_scheme_and_host: true,
+            remove_default_port: true,
+            remove_dot_segments: true,
+            uppercase_percent_encoding: true,
+            decode_unreserved: true,
+            sort_query: false,
+        }
+    }
+}
+
+impl UrlNormalizeOptions {
+    pub fn normalize_percent_encoding(&self, src: &str) -> String {
+        let as_bytes = src.as_bytes();
+        let mut result = String::with_capacity(src.len());
+
+        let mut pos = 0;
+
+        while pos < as_bytes.len() {
+            let Some(escape_pos) = src[pos..].find('%').map(|index| index + pos) else {
+                result.push_str(&src[pos..]);
+                break;
+            };
+
+            result.push_str(&src[pos..escape_pos]);
+
+            if escape_pos + 2 >= as_bytes.len()
+                || !as_bytes[escape_pos + 1].is_ascii_hexdigit()
+                || !as_bytes[escape_pos + 2].is_ascii_hexdigit()
+            {
+                result.push('%');
+                pos = escape_pos + 1;
+                continue;
+            }
+
+            let escape = &src[escape_pos + 1..escape_pos + 3];
+            let decoded = u8::from_str_radix(escape, 16).unwrap();
+
+            if self.decode_unreserved && is_unreserved(decoded) {
+                result.push(decoded as char);
+            } else {
+                result.push('%');
+                if self.uppercase_percent_encoding {
+                    result.push_str(&escape.to_ascii_uppercase());
+                } else {
+                    result.push_str(escape);
+                }
+            }
+
+            pos = escape_pos + 3;
+        }
+
+        result
+    }
+
+    pub fn normalize_path(&self, path: &str) -> String {
+        let path = self.normalize_percent_encoding(path);
+
+        if self.remove_dot_segments {
+            return super::url_reference::remove_dot_segments(&path);
+        }
+
+        path
+    }
+
+    pub fn normalize_query(&self, query: &str) -> String {
+        let query = self.normalize_percent_encoding(query);
+
+        if !self.sort_query {
+            return query;
+        }
+
+        let mut params: Vec<&str> = query.split('&').collect();
+
+        // Stable sort by key, so params with the same key keep their order
+        params.sort_by_key(|param| match param.find('=') {
+            Some(index) => &param[..index],
+            None => param,
+        });
+
+        params.join("&")
+    }
+}
+
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'-' || b == b'.' || b == b'_' || b == b'~'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UrlNormalizeOptions;
+
+    #[test]
+    fn test_normalize_percent_encoding() {
+        let options = UrlNormalizeOptions::default();
+
+        assert_eq!(
+            options.normalize_percent_encoding("%7e%2fa%41%zz%"),
+            "~%2FaA%zz%"
+        );
+
+        let options = UrlNormalizeOptions {
+            decode_unreserved: false,
+            ..Default::default()
+        };
+
+        assert_eq!(options.normalize_percent_encoding("%7e%2f"), "%7E%2F");
+    }
+
+    #[test]
+    fn test_normalize_query() {
+        let options = UrlNormalizeOptions {
+            sort_query: true,
+            ..Default::default()
+        };
+
+        assert_eq!(options.normalize_query("b=2&a=1&c&a=0"), "a=1&a=0&b=2&c");
+    }
+}
@@ -1,10 +1,17 @@
-use rust_extensions::remote_endpoint::RemoteEndpoint;
+use rust_extensions::{remote_endpoint::RemoteEndpoint, StrOrString};
+
+use crate::url_encoder::EncodeSet;
+
+use super::{url_reference::UrlReference, UrlBuilder, UrlNormalizeOptions, UrlParseError};
+
+const UNIX_SOCKET_SCHEME: &str = "http+unix";
 
 pub struct UrlBuilderUnixSocket {
     has_scheme: bool,
     host: String,
     path: String,
     query: String,
+    fragment: String,
 }
 
 impl UrlBuilderUnixSocket {
@@ -25,12 +32,22 @@ impl UrlBuilderUnixSocket {
                 host: host_port.to_string(),
                 path: Default::default(),
                 query: Default::default(),
+                fragment: Default::default(),
             };
         };
 
         let host = host_port[..index].to_string();
 
-        let path_and_query = host_port[index + 1..].to_string();
+        let mut path_and_query = host_port[index + 1..].to_string();
+
+        let fragment = match path_and_query.find('#') {
+            Some(index) => {
+                let fragment = path_and_query[index..].to_string();
+                path_and_query.truncate(index);
+                fragment
+            }
+            None => String::new(),
+        };
 
         let (path, query) = match path_and_query.find('?') {
             Some(index) => {
@@ -46,16 +63,115 @@ impl UrlBuilderUnixSocket {
             host,
             path,
             query,
+            fragment,
+        }
+    }
+
+    pub fn try_new(host_port: &str) -> Result<Self, UrlParseError> {
+        if host_port.is_empty() {
+            return Err(UrlParseError::Empty);
+        }
+
+        let result = Self::new(host_port);
+
+        let host_offset = if result.has_scheme { 11 } else { 0 };
+
+        if result.host.is_empty() {
+            return Err(UrlParseError::EmptyHost { pos: host_offset });
+        }
+
+        let path_offset = host_offset + result.host.len() + 1;
+
+        super::validation::validate_path(&result.path, path_offset)?;
+
+        if !result.query.is_empty() {
+            super::validation::validate_query(
+                &result.query[1..],
+                path_offset + result.path.len() + 1,
+            )?;
+        }
+
+        if !result.fragment.is_empty() {
+            super::validation::validate_fragment(
+                &result.fragment[1..],
+                path_offset + result.path.len() + result.query.len() + 1,
+            )?;
         }
+
+        Ok(result)
     }
 
     pub fn get_remote_endpoint<'s>(&'s self) -> RemoteEndpoint<'s> {
-        RemoteEndpoint::try_parse(&self.host).unwrap()
+        self.try_get_remote_endpoint().unwrap()
+    }
+
+    // Positions are reported from the start of the host, the same way UrlBuilderInner does it
+    pub fn try_get_remote_endpoint<'s>(&'s self) -> Result<RemoteEndpoint<'s>, UrlParseError> {
+        if self.host.is_empty() {
+            return Err(UrlParseError::EmptyHost { pos: 0 });
+        }
+
+        RemoteEndpoint::try_parse(&self.host)
+            .map_err(|_| UrlParseError::InvalidRemoteEndpoint { pos: 0 })
+    }
+
+    pub fn join(&self, reference: &str) -> Result<UrlBuilder, UrlParseError> {
+        let base = UrlReference {
+            scheme: Some(UNIX_SOCKET_SCHEME),
+            authority: Some(&self.host),
+            path: &self.path,
+            query: self.get_query(),
+            fragment: None,
+        };
+
+        let reference = UrlReference::parse(reference);
+
+        let resolved = super::url_reference::resolve(&base, &reference);
+
+        if resolved.scheme != base.scheme || resolved.authority != base.authority {
+            return UrlBuilder::try_new(&resolved.to_string());
+        }
+
+        let mut result = String::new();
+
+        if self.has_scheme {
+            result.push_str("http+unix:/");
+        }
+
+        result.push_str(&self.host);
+        result.push(':');
+        result.push_str(&resolved.path);
+
+        if let Some(query) = resolved.query {
+            result.push('?');
+            result.push_str(query);
+        }
+
+        if let Some(fragment) = resolved.fragment {
+            result.push('#');
+            result.push_str(fragment);
+        }
+
+        UrlBuilder::try_new(&result)
     }
 
     pub fn append_path_segment(&mut self, path_segment: &str) {
         self.path.push('/');
-        self.path.push_str(path_segment);
+        crate::url_encoder::encode_with_and_copy(
+            &mut self.path,
+            EncodeSet::PathSegment,
+            path_segment,
+        );
+    }
+
+    // '/' inside of the path is kept as a separator: `v1/users`
+    pub fn append_path(&mut self, path: &str) {
+        if !self.path.ends_with('/') {
+            self.path.push('/');
+        }
+
+        let path = path.strip_prefix('/').unwrap_or(path);
+        crate::url_encoder::encode_with_and_copy(&mut self.path, EncodeSet::Path, path);
     }
 
     pub fn append_query_param(&mut self, name: &str, value: Option<&str>) {
@@ -65,11 +181,99 @@ impl UrlBuilderUnixSocket {
             self.query.push('&');
         }
 
-        crate::encode_to_url_string_and_copy(&mut self.query, name);
+        crate::url_encoder::encode_with_and_copy(&mut self.query, EncodeSet::QueryKey, name);
 
         if let Some(value) = value {
             self.query.push('=');
-            crate::encode_to_url_string_and_copy(&mut self.query, value);
+            crate::url_encoder::encode_with_and_copy(&mut self.query, EncodeSet::QueryValue, value);
+        }
+    }
+
+    // Socket path is followed by ':', so the symbols which start the next component can not be a part of it
+    pub fn set_host(&mut self, host: &str) -> Result<(), UrlParseError> {
+        if host.is_empty() {
+            return Err(UrlParseError::EmptyHost { pos: 0 });
+        }
+
+        if let Some((pos, symbol)) = host
+            .char_indices()
+            .find(|(_, c)| matches!(c, ':' | '?' | '#'))
+        {
+            return Err(UrlParseError::InvalidHostSymbol { pos, symbol });
+        }
+
+        self.host = host.to_string();
+
+        Ok(())
+    }
+
+    pub fn set_path(&mut self, path: &str) {
+        self.path = super::utils::escape_path(path);
+    }
+
+    pub fn pop_path_segment(&mut self) -> Option<String> {
+        let (path, segment) = super::utils::split_last_path_segment(&self.path)?;
+
+        let path = path.to_string();
+        let segment = segment.to_string();
+
+        self.path = path;
+
+        Some(segment)
+    }
+
+    pub fn clear_query(&mut self) {
+        self.query.clear();
+    }
+
+    pub fn get_query_params(&self, name: &str) -> Vec<Option<StrOrString<'_>>> {
+        match self.get_query() {
+            Some(query) => super::utils::get_query_params(query, name),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn set_query_param(&mut self, name: &str, value: Option<&str>) {
+        let query = super::utils::set_query_param(self.get_query().unwrap_or(""), name, value);
+        self.set_query(&query);
+    }
+
+    pub fn remove_query_param(&mut self, name: &str) {
+        let Some(query) = self.get_query() else {
+            return;
+        };
+
+        let query = super::utils::remove_query_param(query, name);
+        self.set_query(&query);
+    }
+
+    pub fn retain_query_params(&mut self, predicate: impl FnMut(&str, Option<&str>) -> bool) {
+        let Some(query) = self.get_query() else {
+            return;
+        };
+
+        let query = super::utils::retain_query_params(query, predicate);
+        self.set_query(&query);
+    }
+
+    fn set_query(&mut self, query: &str) {
+        self.query.clear();
+
+        if !query.is_empty() {
+            self.query.push('?');
+            self.query.push_str(query);
+        }
+    }
+
+    pub fn normalize(&mut self, options: UrlNormalizeOptions) {
+        self.path = options.normalize_path(&self.path);
+
+        if let Some(query) = self.get_query() {
+            self.query = format!("?{}", options.normalize_query(query));
+        }
+
+        if let Some(fragment) = self.get_fragment() {
+            self.fragment = format!("#{}", options.normalize_percent_encoding(fragment));
         }
     }
 
@@ -109,9 +313,28 @@ impl UrlBuilderUnixSocket {
         }
     }
 
+    pub fn get_fragment(&self) -> Option<&str> {
+        if self.fragment.is_empty() {
+            None
+        } else {
+            Some(&self.fragment[1..])
+        }
+    }
+
+    pub fn set_fragment(&mut self, fragment: &str) {
+        self.fragment.clear();
+        self.fragment.push('#');
+        crate::url_encoder::encode_with_and_copy(&mut self.fragment, EncodeSet::Fragment, fragment);
+    }
+
+    pub fn remove_fragment(&mut self) {
+        self.fragment.clear();
+    }
+
     pub fn to_string(&self) -> String {
-        let mut result =
-            String::with_capacity(self.host.len() + self.path.len() + self.query.len() + 12);
+        let mut result = String::with_capacity(
+            self.host.len() + self.path.len() + self.query.len() + self.fragment.len() + 12,
+        );
 
         if self.has_scheme {
             result.push_str("http+unix:/");
@@ -119,14 +342,18 @@ impl UrlBuilderUnixSocket {
 
         result.push_str(&self.host);
         result.push(':');
-        if self.path.len() > 0 {
+        if !self.path.is_empty() {
             result.push_str(&self.path);
         }
 
-        if self.query.len() > 0 {
+        if !self.query.is_empty() {
             result.push_str(&self.query);
         }
 
+        if !self.fragment.is_empty() {
+            result.push_str(&self.fragment);
+        }
+
         result
     }
 }
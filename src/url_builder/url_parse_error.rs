@@ -0,0 +1,86 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlParseError {
+    Empty,
+    InvalidScheme { pos: usize },
+    InvalidUserInfoSymbol { pos: usize, symbol: char },
+    EmptyHost { pos: usize },
+    InvalidHostSymbol { pos: usize, symbol: char },
+    InvalidIpv6Address { pos: usize },
+    InvalidPort { pos: usize },
+    PortIsOutOfRange { pos: usize },
+    InvalidPathSymbol { pos: usize, symbol: char },
+    InvalidQuerySymbol { pos: usize, symbol: char },
+    InvalidFragmentSymbol { pos: usize, symbol: char },
+    InvalidEscape { pos: usize },
+    // Host and port were accepted by us, but not by RemoteEndpoint
+    InvalidRemoteEndpoint { pos: usize },
+}
+
+impl UrlParseError {
+    /// Byte offset inside the source string where the problem was found
+    pub fn get_pos(&self) -> usize {
+        match self {
+            Self::Empty => 0,
+            Self::InvalidScheme { pos } => *pos,
+            Self::InvalidUserInfoSymbol { pos, .. } => *pos,
+            Self::EmptyHost { pos } => *pos,
+            Self::InvalidHostSymbol { pos, .. } => *pos,
+            Self::InvalidIpv6Address { pos } => *pos,
+            Self::InvalidPort { pos } => *pos,
+            Self::PortIsOutOfRange { pos } => *pos,
+            Self::InvalidPathSymbol { pos, .. } => *pos,
+            Self::InvalidQuerySymbol { pos, .. } => *pos,
+            Self::InvalidFragmentSymbol { pos, .. } => *pos,
+            Self::InvalidEscape { pos } => *pos,
+            Self::InvalidRemoteEndpoint { pos } => *pos,
+        }
+    }
+}
+
+impl Display for UrlParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pos = self.get_pos();
+
+        match self {
+            Self::Empty => write!(f, "Url is empty"),
+            Self::InvalidScheme { .. } => write!(f, "Invalid scheme at position {}", pos),
+            Self::InvalidUserInfoSymbol { symbol, .. } => {
+                write!(
+                    f,
+                    "Invalid user info symbol '{}' at position {}",
+                    symbol, pos
+                )
+            }
+            Self::EmptyHost { .. } => write!(f, "Empty host at position {}", pos),
+            Self::InvalidHostSymbol { symbol, .. } => {
+                write!(f, "Invalid host symbol '{}' at position {}", symbol, pos)
+            }
+            Self::InvalidIpv6Address { .. } => {
+                write!(f, "Invalid IPv6 address at position {}", pos)
+            }
+            Self::InvalidPort { .. } => write!(f, "Invalid port at position {}", pos),
+            Self::PortIsOutOfRange { .. } => write!(f, "Port is out of range at position {}", pos),
+            Self::InvalidPathSymbol { symbol, .. } => {
+                write!(f, "Invalid path symbol '{}' at position {}", symbol, pos)
+            }
+            Self::InvalidQuerySymbol { symbol, .. } => {
+                write!(f, "Invalid query symbol '{}' at position {}", symbol, pos)
+            }
+            Self::InvalidFragmentSymbol { symbol, .. } => {
+                write!(
+                    f,
+                    "Invalid fragment symbol '{}' at position {}",
+                    symbol, pos
+                )
+            }
+            Self::InvalidEscape { .. } => write!(f, "Invalid '%' escape at position {}", pos),
+            Self::InvalidRemoteEndpoint { .. } => {
+                write!(f, "Can not parse remote endpoint at position {}", pos)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UrlParseError {}
@@ -0,0 +1,267 @@
+use std::fmt::Display;
+
+// Components of an URI reference split the way RFC 3986 Appendix B does it
+pub struct UrlReference<'s> {
+    pub scheme: Option<&'s str>,
+    pub authority: Option<&'s str>,
+    pub path: &'s str,
+    pub query: Option<&'s str>,
+    pub fragment: Option<&'s str>,
+}
+
+impl<'s> UrlReference<'s> {
+    pub fn parse(src: &'s str) -> Self {
+        let mut rest = src;
+
+        let mut fragment = None;
+        if let Some(index) = rest.find('#') {
+            fragment = Some(&rest[index + 1..]);
+            rest = &rest[..index];
+        }
+
+        let mut query = None;
+        if let Some(index) = rest.find('?') {
+            query = Some(&rest[index + 1..]);
+            rest = &rest[..index];
+        }
+
+        let mut scheme = None;
+        if let Some(index) = rest.find([':', '/']) {
+            if index > 0 && rest.as_bytes()[index] == b':' {
+                scheme = Some(&rest[..index]);
+                rest = &rest[index + 1..];
+            }
+        }
+
+        let mut authority = None;
+        if let Some(after_slashes) = rest.strip_prefix("//") {
+            let end = after_slashes.find('/').unwrap_or(after_slashes.len());
+            authority = Some(&after_slashes[..end]);
+            rest = &after_slashes[end..];
+        }
+
+        Self {
+            scheme,
+            authority,
+            path: rest,
+            query,
+            fragment,
+        }
+    }
+}
+
+pub struct ResolvedUrl<'s> {
+    pub scheme: Option<&'s str>,
+    pub authority: Option<&'s str>,
+    pub path: String,
+    pub query: Option<&'s str>,
+    pub fragment: Option<&'s str>,
+}
+
+impl<'s> Display for ResolvedUrl<'s> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(scheme) = self.scheme {
+            write!(f, "{}:", scheme)?;
+        }
+
+        if let Some(authority) = self.authority {
+            write!(f, "//{}", authority)?;
+        }
+
+        f.write_str(&self.path)?;
+
+        if let Some(query) = self.query {
+            write!(f, "?{}", query)?;
+        }
+
+        if let Some(fragment) = self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+
+        Ok(())
+    }
+}
+
+// RFC 3986 section 5.2.2
+pub fn resolve<'s>(base: &UrlReference<'s>, reference: &UrlReference<'s>) -> ResolvedUrl<'s> {
+    if reference.scheme.is_some() {
+        return ResolvedUrl {
+            scheme: reference.scheme,
+            authority: reference.authority,
+            path: remove_dot_segments(reference.path),
+            query: reference.query,
+            fragment: reference.fragment,
+        };
+    }
+
+    if reference.authority.is_some() {
+        return ResolvedUrl {
+            scheme: base.scheme,
+            authority: reference.authority,
+            path: remove_dot_segments(reference.path),
+            query: reference.query,
+            fragment: reference.fragment,
+        };
+    }
+
+    if reference.path.is_empty() {
+        return ResolvedUrl {
+            scheme: base.scheme,
+            authority: base.authority,
+            path: base.path.to_string(),
+            query: reference.query.or(base.query),
+            fragment: reference.fragment,
+        };
+    }
+
+    let path = if reference.path.starts_with('/') {
+        remove_dot_segments(reference.path)
+    } else {
+        remove_dot_segments(&merge(base, reference.path))
+    };
+
+    ResolvedUrl {
+        scheme: base.scheme,
+        authority: base.authority,
+        path,
+        query: reference.query,
+        fragment: reference.fragment,
+    }
+}
+
+// RFC 3986 section 5.2.3
+fn merge(base: &UrlReference, reference_path: &str) -> String {
+    if base.authority.is_some() && base.path.is_empty() {
+        return format!("/{}", reference_path);
+    }
+
+    match base.path.rfind('/') {
+        Some(index) => format!("{}{}", &base.path[..index + 1], reference_path),
+        None => reference_path.to_string(),
+    }
+}
+
+// RFC 3986 section 5.2.4
+pub fn remove_dot_segments(path: &str) -> String {
+    let mut input = path;
+    let mut output = String::with_capacity(path.len());
+
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix("./") {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") {
+            input = &input[3..];
+            remove_last_segment(&mut output);
+        } else if input == "/.." {
+            input = "/";
+            remove_last_segment(&mut output);
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let start = if input.starts_with('/') { 1 } else { 0 };
+            let end = match input[start..].find('/') {
+                Some(index) => index + start,
+                None => input.len(),
+            };
+
+            output.push_str(&input[..end]);
+            input = &input[end..];
+        }
+    }
+
+    output
+}
+
+fn remove_last_segment(output: &mut String) {
+    match output.rfind('/') {
+        Some(index) => output.truncate(index),
+        None => output.clear(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "http://a/b/c/d;p?q";
+
+    fn resolve_str(reference: &str) -> String {
+        let base = UrlReference::parse(BASE);
+        let reference = UrlReference::parse(reference);
+        resolve(&base, &reference).to_string()
+    }
+
+    #[test]
+    fn test_rfc_3986_normal_examples() {
+        let cases = [
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            ("g?y#s", "http://a/b/c/g?y#s"),
+            (";x", "http://a/b/c/;x"),
+            ("g;x", "http://a/b/c/g;x"),
+            ("g;x?y#s", "http://a/b/c/g;x?y#s"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../", "http://a/"),
+            ("../../g", "http://a/g"),
+        ];
+
+        for (reference, expected) in cases {
+            assert_eq!(resolve_str(reference), expected, "reference: {}", reference);
+        }
+    }
+
+    #[test]
+    fn test_rfc_3986_abnormal_examples() {
+        let cases = [
+            ("../../../g", "http://a/g"),
+            ("../../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            (".g", "http://a/b/c/.g"),
+            ("g..", "http://a/b/c/g.."),
+            ("..g", "http://a/b/c/..g"),
+            ("./../g", "http://a/b/g"),
+            ("./g/.", "http://a/b/c/g/"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+            ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+            ("g;x=1/../y", "http://a/b/c/y"),
+            ("g?y/./x", "http://a/b/c/g?y/./x"),
+            ("g?y/../x", "http://a/b/c/g?y/../x"),
+            ("g#s/./x", "http://a/b/c/g#s/./x"),
+            ("g#s/../x", "http://a/b/c/g#s/../x"),
+            ("http:g", "http:g"),
+        ];
+
+        for (reference, expected) in cases {
+            assert_eq!(resolve_str(reference), expected, "reference: {}", reference);
+        }
+    }
+
+    #[test]
+    fn test_remove_dot_segments() {
+        assert_eq!(remove_dot_segments("/a/b/c/./../../g"), "/a/g");
+        assert_eq!(remove_dot_segments("mid/content=5/../6"), "mid/6");
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{url_decoder::DecodeOptions, url_encoder::EncodeSet};
+
+// '+' is a literal symbol inside of userinfo
+pub fn decode_user_info(src: &str) -> String {
+    match crate::url_decoder::decode_from_url_query_string_with_options(
+        src,
+        DecodeOptions::rfc3986(),
+    ) {
+        Ok(result) => result,
+        Err(_) => src.to_string(),
+    }
+}
+
+pub fn encode_user_info(dest: &mut String, src: &str) {
+    crate::url_encoder::encode_with_and_copy(dest, EncodeSet::UserInfo, src);
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn test_user_info_round_trip() {
+        let mut encoded = String::new();
+        super::encode_user_info(&mut encoded, "p@ss word+1:");
+
+        assert_eq!(encoded, "p%40ss%20word%2B1%3A");
+        assert_eq!(super::decode_user_info(&encoded), "p@ss word+1:");
+        assert_eq!(super::decode_user_info("a+b%40"), "a+b@");
+    }
+}
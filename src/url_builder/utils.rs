@@ -0,0 +1,203 @@
+use rust_extensions::StrOrString;
+
+use crate::url_encoder::EncodeSet;
+
+pub fn get_default_port(scheme: &str) -> Option<u16> {
+    let scheme = scheme.to_ascii_lowercase();
+
+    match scheme.as_str() {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        "ftp" => Some(21),
+        "amqp" => Some(5672),
+        "amqps" => Some(5671),
+        _ => None,
+    }
+}
+
+// Everything which is not allowed inside of a path is escaped. '/' is kept as a segment separator
+pub fn escape_path(path: &str) -> String {
+    let mut result = String::with_capacity(path.len() + 1);
+
+    if !path.is_empty() && !path.starts_with('/') {
+        result.push('/');
+    }
+
+    crate::url_encoder::encode_with_and_copy(&mut result, EncodeSet::Path, path);
+
+    result
+}
+
+// Splits the path into the path without the last segment and the last segment
+pub fn split_last_path_segment(path: &str) -> Option<(&str, &str)> {
+    if path.len() <= 1 {
+        return None;
+    }
+
+    let index = path.rfind('/')?;
+    Some((&path[..index], &path[index + 1..]))
+}
+
+// Param without '=' is a flag and has no value, which is not the same as an empty value
+pub fn get_query_params<'s>(query: &'s str, name: &str) -> Vec<Option<StrOrString<'s>>> {
+    let mut result = Vec::new();
+
+    for pair in query.split('&') {
+        let (key, value) = split_query_param(pair);
+
+        if decode_query_component(key).as_str() == name {
+            result.push(value.map(decode_query_component));
+        }
+    }
+
+    result
+}
+
+pub fn retain_query_params(
+    query: &str,
+    mut predicate: impl FnMut(&str, Option<&str>) -> bool,
+) -> String {
+    let mut result = String::with_capacity(query.len());
+    let mut first = true;
+
+    for pair in query.split('&') {
+        let (key, value) = split_query_param(pair);
+
+        let key = decode_query_component(key);
+        let value = value.map(decode_query_component);
+
+        if !predicate(key.as_str(), value.as_ref().map(|value| value.as_str())) {
+            continue;
+        }
+
+        if !first {
+            result.push('&');
+        }
+
+        result.push_str(pair);
+        first = false;
+    }
+
+    result
+}
+
+pub fn remove_query_param(query: &str, name: &str) -> String {
+    retain_query_params(query, |key, _| key != name)
+}
+
+// Replaces the first param with the same name and drops the rest of them. Appends the param if there is no such one
+pub fn set_query_param(query: &str, name: &str, value: Option<&str>) -> String {
+    let mut new_param = String::new();
+    crate::url_encoder::encode_with_and_copy(&mut new_param, EncodeSet::QueryKey, name);
+
+    if let Some(value) = value {
+        new_param.push('=');
+        crate::url_encoder::encode_with_and_copy(&mut new_param, EncodeSet::QueryValue, value);
+    }
+
+    if query.is_empty() {
+        return new_param;
+    }
+
+    let mut result = String::with_capacity(query.len() + new_param.len() + 1);
+    let mut replaced = false;
+
+    for pair in query.split('&') {
+        let (key, _) = split_query_param(pair);
+
+        let pair = if decode_query_component(key).as_str() == name {
+            if replaced {
+                continue;
+            }
+
+            replaced = true;
+            new_param.as_str()
+        } else {
+            pair
+        };
+
+        if !result.is_empty() {
+            result.push('&');
+        }
+
+        result.push_str(pair);
+    }
+
+    if !replaced {
+        result.push('&');
+        result.push_str(&new_param);
+    }
+
+    result
+}
+
+fn split_query_param(pair: &str) -> (&str, Option<&str>) {
+    match pair.find('=') {
+        Some(index) => (&pair[..index], Some(&pair[index + 1..])),
+        None => (pair, None),
+    }
+}
+
+fn decode_query_component(src: &str) -> StrOrString<'_> {
+    if !src.contains(['%', '+']) {
+        return StrOrString::create_as_str(src);
+    }
+
+    match crate::url_decoder::decode_from_url_query_string(src) {
+        Ok(result) => StrOrString::create_as_string(result),
+        Err(_) => StrOrString::create_as_str(src),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn test_escape_path() {
+        assert_eq!(super::escape_path("a/b"), "/a/b");
+        assert_eq!(super::escape_path("/a?b#c"), "/a%3Fb%23c");
+        assert_eq!(super::escape_path("a b\n"), "/a%20b%0A");
+        assert_eq!(super::escape_path(""), "");
+    }
+
+    #[test]
+    fn test_remove_query_param() {
+        assert_eq!(super::remove_query_param("a=1&b=2&a=3&c", "a"), "b=2&c");
+        assert_eq!(super::remove_query_param("my%20key=1&b=2", "my key"), "b=2");
+        assert_eq!(super::remove_query_param("a=1", "a"), "");
+    }
+
+    #[test]
+    fn test_get_query_params() {
+        let result = super::get_query_params("a=1&b=2&a=hello+world&c&a&a=", "a");
+        let result: Vec<Option<&str>> = result
+            .iter()
+            .map(|itm| itm.as_ref().map(|itm| itm.as_str()))
+            .collect();
+
+        assert_eq!(result, vec![Some("1"), Some("hello world"), None, Some("")]);
+    }
+
+    #[test]
+    fn test_set_query_param() {
+        assert_eq!(
+            super::set_query_param("page=1&size=10&page=2", "page", Some("3")),
+            "page=3&size=10"
+        );
+        assert_eq!(
+            super::set_query_param("size=10", "page", Some("a b")),
+            "size=10&page=a+b"
+        );
+        assert_eq!(super::set_query_param("", "flag", None), "flag");
+    }
+
+    #[test]
+    fn test_retain_query_params() {
+        let result =
+            super::retain_query_params("a=1&b=2&utm_source=x&c", |key, _| !key.starts_with("utm_"));
+        assert_eq!(result, "a=1&b=2&c");
+
+        let result = super::retain_query_params("a=1&b=2&c", |_, value| value == Some("2"));
+        assert_eq!(result, "b=2");
+    }
+}
@@ -0,0 +1,194 @@
+use std::net::Ipv6Addr;
+
+use super::UrlParseError;
+
+pub fn validate_scheme(scheme: &str, offset: usize) -> Result<(), UrlParseError> {
+    let as_bytes = scheme.as_bytes();
+
+    if as_bytes.is_empty() || !as_bytes[0].is_ascii_alphabetic() {
+        return Err(UrlParseError::InvalidScheme { pos: offset });
+    }
+
+    for (i, b) in as_bytes.iter().enumerate() {
+        if !(b.is_ascii_alphanumeric() || *b == b'+' || *b == b'-' || *b == b'.') {
+            return Err(UrlParseError::InvalidScheme { pos: offset + i });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_user_info(user_info: &str, offset: usize) -> Result<(), UrlParseError> {
+    for (i, c) in user_info.char_indices() {
+        if c == '%' {
+            validate_escape(user_info, i, offset)?;
+            continue;
+        }
+
+        if !(c == ':' || is_host_symbol(c)) {
+            return Err(UrlParseError::InvalidUserInfoSymbol {
+                pos: offset + i,
+                symbol: c,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_host(host: &str, offset: usize) -> Result<(), UrlParseError> {
+    if host.is_empty() {
+        return Err(UrlParseError::EmptyHost { pos: offset });
+    }
+
+    if let Some(ipv6) = host.strip_prefix('[') {
+        return validate_ipv6_literal(ipv6, offset);
+    }
+
+    for (i, c) in host.char_indices() {
+        if c == '%' {
+            validate_escape(host, i, offset)?;
+            continue;
+        }
+
+        if !is_host_symbol(c) {
+            return Err(UrlParseError::InvalidHostSymbol {
+                pos: offset + i,
+                symbol: c,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// RFC 6874: IPv6address [ "%25" ZoneID ] "]"
+fn validate_ipv6_literal(ipv6: &str, offset: usize) -> Result<(), UrlParseError> {
+    let Some(ipv6) = ipv6.strip_suffix(']') else {
+        return Err(UrlParseError::InvalidIpv6Address { pos: offset });
+    };
+
+    let (address, zone_id) = match ipv6.find("%25") {
+        Some(index) => (&ipv6[..index], Some(&ipv6[index + 3..])),
+        None => (ipv6, None),
+    };
+
+    if address.parse::<Ipv6Addr>().is_err() {
+        return Err(UrlParseError::InvalidIpv6Address { pos: offset + 1 });
+    }
+
+    if let Some(zone_id) = zone_id {
+        let zone_id_is_valid = !zone_id.is_empty()
+            && zone_id
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~'));
+
+        if !zone_id_is_valid {
+            return Err(UrlParseError::InvalidIpv6Address {
+                pos: offset + 1 + address.len(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_port(port: &str, offset: usize) -> Result<u16, UrlParseError> {
+    if port.is_empty() || !port.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(UrlParseError::InvalidPort { pos: offset });
+    }
+
+    match port.parse::<u16>() {
+        Ok(port) => Ok(port),
+        Err(_) => Err(UrlParseError::PortIsOutOfRange { pos: offset }),
+    }
+}
+
+pub fn validate_path(path: &str, offset: usize) -> Result<(), UrlParseError> {
+    for (i, c) in path.char_indices() {
+        if c == '%' {
+            validate_escape(path, i, offset)?;
+            continue;
+        }
+
+        if is_forbidden_symbol(c) {
+            return Err(UrlParseError::InvalidPathSymbol {
+                pos: offset + i,
+                symbol: c,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_query(query: &str, offset: usize) -> Result<(), UrlParseError> {
+    for (i, c) in query.char_indices() {
+        if c == '%' {
+            validate_escape(query, i, offset)?;
+            continue;
+        }
+
+        if is_forbidden_symbol(c) {
+            return Err(UrlParseError::InvalidQuerySymbol {
+                pos: offset + i,
+                symbol: c,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_fragment(fragment: &str, offset: usize) -> Result<(), UrlParseError> {
+    for (i, c) in fragment.char_indices() {
+        if c == '%' {
+            validate_escape(fragment, i, offset)?;
+            continue;
+        }
+
+        if c == '#' || is_forbidden_symbol(c) {
+            return Err(UrlParseError::InvalidFragmentSymbol {
+                pos: offset + i,
+                symbol: c,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_escape(src: &str, index: usize, offset: usize) -> Result<(), UrlParseError> {
+    let as_bytes = src.as_bytes();
+
+    if index + 2 >= as_bytes.len() {
+        return Err(UrlParseError::InvalidEscape { pos: offset + index });
+    }
+
+    if !as_bytes[index + 1].is_ascii_hexdigit() || !as_bytes[index + 2].is_ascii_hexdigit() {
+        return Err(UrlParseError::InvalidEscape { pos: offset + index });
+    }
+
+    Ok(())
+}
+
+// RFC 3986: unreserved / sub-delims
+fn is_host_symbol(c: char) -> bool {
+    if c.is_ascii_alphanumeric() {
+        return true;
+    }
+
+    matches!(
+        c,
+        '-' | '.' | '_' | '~' | '!' | '$' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | ';' | '='
+    )
+}
+
+// Symbols which are never allowed to appear unescaped inside an URL
+fn is_forbidden_symbol(c: char) -> bool {
+    if c.is_ascii_control() {
+        return true;
+    }
+
+    matches!(c, ' ' | '"' | '<' | '>' | '\\' | '^' | '`' | '{' | '|' | '}')
+}
@@ -1,53 +1,97 @@
+use std::borrow::Cow;
+
 use rust_extensions::StrOrString;
 
-use super::{UrlDecodeError, UrlDecoder};
+use super::{DecodeMode, DecodeOptions, UrlDecodeError, UrlDecodeErrorKind, UrlDecoder};
 
 pub fn decode_from_url_query_string<'s>(src: &'s str) -> Result<String, UrlDecodeError> {
-    if !has_escape(src.as_bytes()) {
-        return Ok(src.to_string());
+    decode_from_url_query_string_with_options(src, DecodeOptions::default())
+}
+
+pub fn decode_from_url_query_string_with_options(
+    src: &str,
+    options: DecodeOptions,
+) -> Result<String, UrlDecodeError> {
+    let result = match decode_to_bytes_with_options(src, options)? {
+        Cow::Borrowed(_) => return Ok(src.to_string()),
+        Cow::Owned(result) => result,
+    };
+
+    match String::from_utf8(result) {
+        Ok(result) => Ok(result),
+        Err(err) if options.mode == DecodeMode::Lossy => {
+            Ok(String::from_utf8_lossy(err.as_bytes()).into_owned())
+        }
+        Err(err) => Err(utf8_error(src, options, err.utf8_error().valid_up_to())),
     }
+}
 
-    let mut result: Vec<u8> = Vec::with_capacity(src.len());
-    let mut url_decoder = UrlDecoder::new(src);
+// Decodes once again up to the bad sequence to find out where it starts in the input
+fn utf8_error(src: &str, options: DecodeOptions, valid_up_to: usize) -> UrlDecodeError {
+    let mut url_decoder = UrlDecoder::new_with_options(src, options);
 
-    while let Some(next_one) = url_decoder.get_next()? {
-        result.push(next_one);
+    for _ in 0..valid_up_to {
+        let _ = url_decoder.get_next();
     }
 
-    Ok(String::from_utf8(result)?)
+    UrlDecodeError::new(
+        UrlDecodeErrorKind::InvalidUtf8 { valid_up_to },
+        url_decoder.get_pos(),
+    )
 }
 
-pub fn decode_as_str_or_string<'s>(src: &'s str) -> Result<StrOrString<'s>, UrlDecodeError> {
-    if !has_escape(src.as_bytes()) {
-        return Ok(StrOrString::create_as_str(src));
+// Binary payloads (HMACs, protobuf blobs) are not required to be UTF-8
+pub fn decode_to_bytes(src: &str) -> Result<Cow<'_, [u8]>, UrlDecodeError> {
+    decode_to_bytes_with_options(src, DecodeOptions::default())
+}
+
+pub fn decode_to_bytes_with_options(
+    src: &str,
+    options: DecodeOptions,
+) -> Result<Cow<'_, [u8]>, UrlDecodeError> {
+    if !options.needs_decoding(src) {
+        return Ok(Cow::Borrowed(src.as_bytes()));
     }
 
-    let mut result = String::with_capacity(src.len());
-    let mut url_decoder = UrlDecoder::new(src);
+    let mut result: Vec<u8> = Vec::with_capacity(src.len());
+    UrlDecoder::new_with_options(src, options).decode_to(&mut result)?;
 
-    while let Some(next_one) = url_decoder.get_next()? {
-        result.push(next_one as char);
+    Ok(Cow::Owned(result))
+}
+
+pub fn decode_as_str_or_string<'s>(src: &'s str) -> Result<StrOrString<'s>, UrlDecodeError> {
+    decode_as_str_or_string_with_options(src, DecodeOptions::default())
+}
+
+pub fn decode_as_str_or_string_with_options(
+    src: &str,
+    options: DecodeOptions,
+) -> Result<StrOrString<'_>, UrlDecodeError> {
+    if !options.needs_decoding(src) {
+        return Ok(StrOrString::create_as_str(src));
     }
 
-    return Ok(StrOrString::create_as_string(result));
+    let result = decode_from_url_query_string_with_options(src, options)?;
+    Ok(StrOrString::create_as_string(result))
 }
 
-fn has_escape(src: &[u8]) -> bool {
-    for itm in src {
-        if *itm == b'%' {
-            return true;
-        }
+// WHATWG-like decoding: `%zz` is kept as it is, invalid UTF-8 becomes U+FFFD
+pub fn decode_lossy(src: &str) -> Cow<'_, str> {
+    let options = DecodeOptions::default().with_mode(DecodeMode::Lossy);
 
-        if *itm == b'+' {
-            return true;
-        }
+    if !options.needs_decoding(src) {
+        return Cow::Borrowed(src);
     }
 
-    false
+    match decode_from_url_query_string_with_options(src, options) {
+        Ok(result) => Cow::Owned(result),
+        Err(_) => Cow::Borrowed(src),
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::url_decoder::UrlDecodeErrorKind;
 
     #[test]
     fn test_url_decoding() {
@@ -73,9 +117,12 @@ mod tests {
         let result = super::decode_from_url_query_string(value);
 
         assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .msg
+
+        let err = result.unwrap_err();
+        assert_eq!(err.kind, UrlDecodeErrorKind::IncompleteEscape);
+        assert_eq!(err.pos, 3);
+        assert!(err
+            .to_string()
             .contains("Unexpected end of input after '%' escape"));
     }
 
@@ -86,7 +133,11 @@ mod tests {
         let result = super::decode_from_url_query_string(value);
 
         assert!(result.is_err());
-        assert!(result.unwrap_err().msg.contains("Invalid escape char"));
+
+        let err = result.unwrap_err();
+        assert_eq!(err.kind, UrlDecodeErrorKind::InvalidHex { found: b'Z' });
+        assert_eq!(err.pos, 5);
+        assert!(err.to_string().contains("Invalid escape char 'Z'"));
     }
 
     #[test]
@@ -97,10 +148,15 @@ mod tests {
         let result = super::decode_from_url_query_string(value);
 
         assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .msg
-            .contains("Can not decode Utf8 string"));
+
+        let err = result.unwrap_err();
+        assert_eq!(err.kind, UrlDecodeErrorKind::InvalidUtf8 { valid_up_to: 0 });
+        assert_eq!(err.pos, 0);
+        assert!(err.to_string().contains("Can not decode Utf8 string"));
+
+        let err = super::decode_from_url_query_string("a+b%C3%28").unwrap_err();
+        assert_eq!(err.kind, UrlDecodeErrorKind::InvalidUtf8 { valid_up_to: 3 });
+        assert_eq!(err.pos, 3);
     }
 
     #[test]
@@ -111,4 +167,63 @@ mod tests {
 
         assert_eq!("value1|value2", result.unwrap().as_str());
     }
+
+    #[test]
+    fn test_plus_as_space_can_be_disabled() {
+        use crate::url_decoder::DecodeOptions;
+
+        let value = "a+b%20c";
+
+        let result = super::decode_from_url_query_string(value).unwrap();
+        assert_eq!("a b c", result);
+
+        let options = DecodeOptions::default().with_plus_as_space(false);
+
+        let result = super::decode_from_url_query_string_with_options(value, options).unwrap();
+        assert_eq!("a+b c", result);
+
+        let result = super::decode_as_str_or_string_with_options("a+b", options).unwrap();
+        assert_eq!("a+b", result.as_str());
+    }
+
+    #[test]
+    fn test_decode_modes() {
+        use crate::url_decoder::{DecodeMode, DecodeOptions};
+
+        let lenient = DecodeOptions::default().with_mode(DecodeMode::Lenient);
+
+        for (value, expected) in [
+            ("100%", "100%"),
+            ("a%2", "a%2"),
+            ("%zz%20b", "%zz b"),
+            ("%%41", "%A"),
+        ] {
+            assert!(super::decode_from_url_query_string(value).is_err());
+
+            let result = super::decode_from_url_query_string_with_options(value, lenient);
+            assert_eq!(expected, result.unwrap());
+        }
+
+        assert!(super::decode_from_url_query_string_with_options("%C3%28", lenient).is_err());
+
+        assert_eq!("\u{FFFD}(", super::decode_lossy("%C3%28"));
+        assert_eq!("50% off", super::decode_lossy("50%+off"));
+        assert_eq!(
+            "Жук",
+            super::decode_as_str_or_string("%D0%96%D1%83%D0%BA")
+                .unwrap()
+                .as_str()
+        );
+    }
+
+    #[test]
+    fn test_decode_to_bytes() {
+        let result = super::decode_to_bytes("%00%FF%C3a+").unwrap();
+        assert_eq!(result.as_ref(), &[0x00, 0xFF, 0xC3, b'a', b' ']);
+
+        let result = super::decode_to_bytes("plain").unwrap();
+        assert!(matches!(result, std::borrow::Cow::Borrowed(b"plain")));
+
+        assert!(super::decode_to_bytes("%F").is_err());
+    }
 }
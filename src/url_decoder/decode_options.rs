@@ -0,0 +1,78 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PairSeparator {
+    #[default]
+    Ampersand,
+    // Legacy clients: `a=1;b=2`
+    Semicolon,
+    Both,
+}
+
+impl PairSeparator {
+    pub fn is_separator(&self, c: char) -> bool {
+        match self {
+            Self::Ampersand => c == '&',
+            Self::Semicolon => c == ';',
+            Self::Both => c == '&' || c == ';',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodeMode {
+    // Broken escapes and invalid UTF-8 are errors
+    #[default]
+    Strict,
+    // Broken escapes (`%zz`, trailing `%`) are kept as they are. Invalid UTF-8 is still an error
+    Lenient,
+    // Same as Lenient, but invalid UTF-8 is replaced with U+FFFD. Never fails
+    Lossy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeOptions {
+    // '+' means a space in application/x-www-form-urlencoded, but it is a literal '+' in RFC 3986
+    pub plus_as_space: bool,
+    pub separator: PairSeparator,
+    pub mode: DecodeMode,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self {
+            plus_as_space: true,
+            separator: PairSeparator::Ampersand,
+            mode: DecodeMode::Strict,
+        }
+    }
+}
+
+impl DecodeOptions {
+    // Path segments, userinfo and RFC 3986 query strings
+    pub fn rfc3986() -> Self {
+        Self {
+            plus_as_space: false,
+            separator: PairSeparator::Ampersand,
+            mode: DecodeMode::Strict,
+        }
+    }
+
+    pub fn with_plus_as_space(mut self, plus_as_space: bool) -> Self {
+        self.plus_as_space = plus_as_space;
+        self
+    }
+
+    pub fn with_separator(mut self, separator: PairSeparator) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    pub fn with_mode(mut self, mode: DecodeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn needs_decoding(&self, src: &str) -> bool {
+        src.bytes()
+            .any(|b| b == b'%' || (self.plus_as_space && b == b'+'))
+    }
+}
@@ -1,22 +1,48 @@
-use std::{str::Utf8Error, string::FromUtf8Error};
+use std::fmt::Display;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UrlDecodeErrorKind {
+    // '%' is not followed by two symbols
+    IncompleteEscape,
+    InvalidHex { found: u8 },
+    // Offset is inside of the decoded bytes
+    InvalidUtf8 { valid_up_to: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UrlDecodeError {
-    pub msg: String,
+    pub kind: UrlDecodeErrorKind,
+    // Byte offset inside of the input string
+    pub pos: usize,
 }
 
-impl From<Utf8Error> for UrlDecodeError {
-    fn from(src: Utf8Error) -> Self {
-        return Self {
-            msg: format!("Can not decode Utf8 string. Reason: {}", src),
-        };
+impl UrlDecodeError {
+    pub fn new(kind: UrlDecodeErrorKind, pos: usize) -> Self {
+        Self { kind, pos }
     }
 }
 
-impl From<FromUtf8Error> for UrlDecodeError {
-    fn from(src: FromUtf8Error) -> Self {
-        Self {
-            msg: format!("Can not decode Utf8 string. Reason: {}", src),
+impl Display for UrlDecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            UrlDecodeErrorKind::IncompleteEscape => write!(
+                f,
+                "Unexpected end of input after '%' escape at position {}",
+                self.pos
+            ),
+            UrlDecodeErrorKind::InvalidHex { found } => write!(
+                f,
+                "Invalid escape char '{}' at position {}",
+                found.escape_ascii(),
+                self.pos
+            ),
+            UrlDecodeErrorKind::InvalidUtf8 { .. } => write!(
+                f,
+                "Can not decode Utf8 string. Invalid byte sequence at position {}",
+                self.pos
+            ),
         }
     }
 }
+
+impl std::error::Error for UrlDecodeError {}
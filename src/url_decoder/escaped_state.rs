@@ -1,4 +1,4 @@
-use super::UrlDecodeError;
+use super::UrlDecodeErrorKind;
 
 pub struct EscapedState {
     buffer: [u8; 3],
@@ -13,7 +13,7 @@ impl EscapedState {
         }
     }
 
-    pub fn get_next(&mut self, next_char: u8) -> Result<Option<u8>, UrlDecodeError> {
+    pub fn get_next(&mut self, next_char: u8) -> Result<Option<u8>, UrlDecodeErrorKind> {
         self.buffer[self.pos] = next_char;
         self.pos += 1;
 
@@ -29,7 +29,7 @@ impl EscapedState {
     }
 }
 
-pub fn decode_escaped(encoded: &[u8]) -> Result<u8, UrlDecodeError> {
+pub fn decode_escaped(encoded: &[u8]) -> Result<u8, UrlDecodeErrorKind> {
     let b0 = decode_hex_symbol(encoded[1])?;
     let b1 = decode_hex_symbol(encoded[2])?;
 
@@ -38,24 +38,9 @@ pub fn decode_escaped(encoded: &[u8]) -> Result<u8, UrlDecodeError> {
     Ok(symbol)
 }
 
-const ZERO: u8 = '0' as u8;
-const A_LOWER: u8 = 'a' as u8 - 10;
-const A_CAPITAL: u8 = 'A' as u8 - 10;
-
-fn decode_hex_symbol(c: u8) -> Result<u8, UrlDecodeError> {
-    if c >= '0' as u8 && c <= '9' as u8 {
-        return Ok(c - ZERO);
-    }
-
-    if c >= 'a' as u8 && c <= 'f' as u8 {
-        return Ok(c - A_LOWER);
+fn decode_hex_symbol(c: u8) -> Result<u8, UrlDecodeErrorKind> {
+    match super::decode_hex_digit(c) {
+        Some(result) => Ok(result),
+        None => Err(UrlDecodeErrorKind::InvalidHex { found: c }),
     }
-
-    if c >= 'A' as u8 && c <= 'F' as u8 {
-        return Ok(c - A_CAPITAL);
-    }
-
-    return Err(UrlDecodeError {
-        msg: format!("Invalid escape char {}", c),
-    });
 }
@@ -0,0 +1,43 @@
+const INVALID: u8 = 0xFF;
+
+// Value of every hex digit. Everything else is INVALID
+const HEX_VALUES: [u8; 256] = build_hex_values();
+
+const fn build_hex_values() -> [u8; 256] {
+    let mut result = [INVALID; 256];
+    let mut i = 0;
+
+    while i < 10 {
+        result[b'0' as usize + i] = i as u8;
+        i += 1;
+    }
+
+    i = 0;
+
+    while i < 6 {
+        result[b'a' as usize + i] = 10 + i as u8;
+        result[b'A' as usize + i] = 10 + i as u8;
+        i += 1;
+    }
+
+    result
+}
+
+pub fn decode_hex_digit(c: u8) -> Option<u8> {
+    match HEX_VALUES[c as usize] {
+        INVALID => None,
+        value => Some(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn test_decode_hex_digit() {
+        for c in 0..=255u8 {
+            let expected = (c as char).to_digit(16).map(|value| value as u8);
+            assert_eq!(super::decode_hex_digit(c), expected);
+        }
+    }
+}
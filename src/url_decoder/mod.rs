@@ -1,10 +1,16 @@
 mod decode_from_url_query_string;
+mod decode_options;
 mod error;
 mod escaped_state;
+mod hex_table;
 mod normal_state;
+mod url_decode_reader;
 mod url_decode_state;
 mod url_decoder;
 
 pub use decode_from_url_query_string::*;
-pub use error::UrlDecodeError;
+pub use decode_options::*;
+pub use error::{UrlDecodeError, UrlDecodeErrorKind};
+pub use hex_table::decode_hex_digit;
+pub use url_decode_reader::UrlDecodeReader;
 pub use url_decoder::UrlDecoder;
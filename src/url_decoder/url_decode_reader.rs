@@ -0,0 +1,227 @@
+use std::{
+    collections::VecDeque,
+    io::{ErrorKind, Read},
+};
+
+use super::{
+    escaped_state::EscapedState, normal_state::NormalState, url_decode_state::UrlDecodeState,
+    DecodeMode, DecodeOptions, UrlDecodeError, UrlDecodeErrorKind,
+};
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+// Decodes the stream on the fly. The state is kept between the chunks,
+// so an escape split across them (`%2` + `F`) is decoded as a whole.
+// Output is bytes, so there is no UTF-8 validation and Lossy works the same way as Lenient.
+// Decode errors come as io::ErrorKind::InvalidData with UrlDecodeError inside
+pub struct UrlDecodeReader<R: Read> {
+    inner: R,
+    buffer: Vec<u8>,
+    buffer_pos: usize,
+    buffer_len: usize,
+    state: UrlDecodeState,
+    options: DecodeOptions,
+    // Bytes read from inner so far
+    pos: usize,
+    escape_start: usize,
+    // Bytes of the current escape after '%'. Lenient modes replay them if the escape is broken
+    escaped: Vec<u8>,
+    replay: VecDeque<u8>,
+}
+
+impl<R: Read> UrlDecodeReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self::with_options(inner, DecodeOptions::default())
+    }
+
+    pub fn with_options(inner: R, options: DecodeOptions) -> Self {
+        Self {
+            inner,
+            buffer: vec![0u8; BUFFER_SIZE],
+            buffer_pos: 0,
+            buffer_len: 0,
+            state: UrlDecodeState::Normal(NormalState {}),
+            options,
+            pos: 0,
+            escape_start: 0,
+            escaped: Vec::with_capacity(2),
+            replay: VecDeque::with_capacity(2),
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn next_input(&mut self) -> std::io::Result<Option<u8>> {
+        if let Some(b) = self.replay.pop_front() {
+            return Ok(Some(b));
+        }
+
+        if self.buffer_pos == self.buffer_len {
+            self.buffer_len = self.inner.read(&mut self.buffer)?;
+            self.buffer_pos = 0;
+
+            if self.buffer_len == 0 {
+                return Ok(None);
+            }
+        }
+
+        let result = self.buffer[self.buffer_pos];
+        self.buffer_pos += 1;
+        self.pos += 1;
+
+        Ok(Some(result))
+    }
+
+    fn has_buffered_input(&self) -> bool {
+        !self.replay.is_empty() || self.buffer_pos < self.buffer_len
+    }
+
+    // Same as UrlDecoder: lenient modes give '%' back and continue right after it
+    fn broken_escape(&mut self, err: UrlDecodeError) -> std::io::Result<u8> {
+        if self.options.mode == DecodeMode::Strict {
+            return Err(std::io::Error::new(ErrorKind::InvalidData, err));
+        }
+
+        for b in self.escaped.drain(..).rev() {
+            self.replay.push_front(b);
+        }
+
+        self.state = UrlDecodeState::Normal(NormalState {});
+        Ok(b'%')
+    }
+}
+
+impl<R: Read> Read for UrlDecodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut written = 0;
+
+        while written < buf.len() {
+            // Do not block on the inner reader if there is something to return already
+            if written > 0 && !self.has_buffered_input() {
+                break;
+            }
+
+            let Some(next_char) = self.next_input()? else {
+                if !matches!(self.state, UrlDecodeState::Escaped(_)) {
+                    break;
+                }
+
+                let err =
+                    UrlDecodeError::new(UrlDecodeErrorKind::IncompleteEscape, self.escape_start);
+                buf[written] = self.broken_escape(err)?;
+                written += 1;
+                continue;
+            };
+
+            match &mut self.state {
+                UrlDecodeState::Normal(state) => {
+                    if state.get_next(next_char) {
+                        buf[written] = if next_char == b'+' && self.options.plus_as_space {
+                            b' '
+                        } else {
+                            next_char
+                        };
+                        written += 1;
+                        continue;
+                    }
+
+                    self.escape_start = self.pos - 1;
+                    self.escaped.clear();
+                    self.state = UrlDecodeState::Escaped(EscapedState::new(next_char));
+                }
+                UrlDecodeState::Escaped(state) => {
+                    let next_result = state.get_next(next_char);
+                    self.escaped.push(next_char);
+
+                    match next_result {
+                        Ok(Some(next_symbol)) => {
+                            self.state = UrlDecodeState::Normal(NormalState {});
+                            buf[written] = next_symbol;
+                            written += 1;
+                        }
+                        Ok(None) => {}
+                        Err(kind) => {
+                            let err = UrlDecodeError::new(kind, self.pos - 1);
+                            buf[written] = self.broken_escape(err)?;
+                            written += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use crate::url_decoder::{DecodeMode, DecodeOptions, UrlDecodeError, UrlDecodeErrorKind};
+
+    use super::UrlDecodeReader;
+
+    // Gives the data away by chunks of the given size
+    struct ChunkedReader<'s> {
+        data: &'s [u8],
+        chunk_size: usize,
+    }
+
+    impl<'s> Read for ChunkedReader<'s> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.chunk_size.min(buf.len()).min(self.data.len());
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    fn read_all(src: &str, chunk_size: usize, options: DecodeOptions) -> std::io::Result<Vec<u8>> {
+        let inner = ChunkedReader {
+            data: src.as_bytes(),
+            chunk_size,
+        };
+
+        let mut result = Vec::new();
+        UrlDecodeReader::with_options(inner, options).read_to_end(&mut result)?;
+        Ok(result)
+    }
+
+    #[test]
+    fn test_escapes_split_across_chunks() {
+        let src = "name=John+Doe&city=%D0%9A%D0%B8%D1%97%D0%B2&sig=%00%FF";
+
+        for chunk_size in 1..8 {
+            let result = read_all(src, chunk_size, DecodeOptions::default()).unwrap();
+
+            let mut expected = "name=John Doe&city=Київ&sig=".as_bytes().to_vec();
+            expected.extend_from_slice(&[0x00, 0xFF]);
+
+            assert_eq!(result, expected, "chunk size {}", chunk_size);
+        }
+    }
+
+    #[test]
+    fn test_broken_escapes() {
+        for chunk_size in [1, 2, 64] {
+            let err = read_all("ab%2Z", chunk_size, DecodeOptions::default()).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+            let err = err
+                .into_inner()
+                .unwrap()
+                .downcast::<UrlDecodeError>()
+                .unwrap();
+            assert_eq!(err.kind, UrlDecodeErrorKind::InvalidHex { found: b'Z' });
+            assert_eq!(err.pos, 4);
+
+            let lenient = DecodeOptions::default().with_mode(DecodeMode::Lenient);
+
+            let result = read_all("%zz%%41 100%", chunk_size, lenient).unwrap();
+            assert_eq!(result, b"%zz%A 100%");
+        }
+    }
+}
@@ -1,32 +1,46 @@
 use crate::url_decoder::escaped_state::EscapedState;
 
-use super::{normal_state::NormalState, url_decode_state::UrlDecodeState, UrlDecodeError};
+use super::{
+    decode_hex_digit, normal_state::NormalState, url_decode_state::UrlDecodeState, DecodeMode,
+    DecodeOptions, UrlDecodeError, UrlDecodeErrorKind,
+};
 
 pub struct UrlDecoder<'s> {
     src: &'s [u8],
     pos: usize,
     state: UrlDecodeState,
+    plus_as_space: bool,
+    mode: DecodeMode,
+    // Position of the '%' which started the current escape
+    escape_start: usize,
 }
 
 impl<'s> UrlDecoder<'s> {
     pub fn new(src: &'s str) -> Self {
+        Self::new_with_options(src, DecodeOptions::default())
+    }
+
+    pub fn new_with_options(src: &'s str, options: DecodeOptions) -> Self {
         Self {
             src: src.as_bytes(),
             pos: 0,
             state: UrlDecodeState::Normal(NormalState {}),
+            plus_as_space: options.plus_as_space,
+            mode: options.mode,
+            escape_start: 0,
         }
     }
 
     pub fn get_next(&mut self) -> Result<Option<u8>, UrlDecodeError> {
         loop {
             if self.pos >= self.src.len() {
-                return if matches!(self.state, UrlDecodeState::Escaped(_)) {
-                    Err(UrlDecodeError {
-                        msg: "Unexpected end of input after '%' escape".to_string(),
-                    })
-                } else {
-                    Ok(None)
-                };
+                if !matches!(self.state, UrlDecodeState::Escaped(_)) {
+                    return Ok(None);
+                }
+
+                let err =
+                    UrlDecodeError::new(UrlDecodeErrorKind::IncompleteEscape, self.escape_start);
+                return self.broken_escape(err);
             }
 
             let next_char = self.src[self.pos];
@@ -36,17 +50,24 @@ impl<'s> UrlDecoder<'s> {
             match &mut self.state {
                 UrlDecodeState::Normal(state) => {
                     if state.get_next(next_char) {
-                        if next_char == b'+' {
+                        if next_char == b'+' && self.plus_as_space {
                             return Ok(Some(32));
                         } else {
                             return Ok(Some(next_char));
                         }
                     }
 
+                    self.escape_start = self.pos - 1;
                     self.state = UrlDecodeState::Escaped(EscapedState::new(next_char));
                 }
                 UrlDecodeState::Escaped(state) => {
-                    let next_result = state.get_next(next_char)?;
+                    let next_result = match state.get_next(next_char) {
+                        Ok(result) => result,
+                        Err(kind) => {
+                            let err = UrlDecodeError::new(kind, self.pos - 1);
+                            return self.broken_escape(err);
+                        }
+                    };
 
                     if let Some(next_symbol) = next_result {
                         self.state = UrlDecodeState::Normal(NormalState {});
@@ -56,4 +77,51 @@ impl<'s> UrlDecoder<'s> {
             }
         }
     }
+
+    // Same as calling get_next till the end, but runs without escapes are copied at once
+    pub fn decode_to(&mut self, dest: &mut Vec<u8>) -> Result<(), UrlDecodeError> {
+        loop {
+            if matches!(self.state, UrlDecodeState::Normal(_)) {
+                let rest = &self.src[self.pos..];
+                let run = rest
+                    .iter()
+                    .position(|b| *b == b'%' || (*b == b'+' && self.plus_as_space))
+                    .unwrap_or(rest.len());
+
+                dest.extend_from_slice(&rest[..run]);
+                self.pos += run;
+
+                // Complete escape is decoded right away, broken ones go through the state machine
+                if let [b'%', high, low, ..] = self.src[self.pos..] {
+                    if let (Some(high), Some(low)) = (decode_hex_digit(high), decode_hex_digit(low))
+                    {
+                        dest.push(high * 16 + low);
+                        self.pos += 3;
+                        continue;
+                    }
+                }
+            }
+
+            match self.get_next()? {
+                Some(next_one) => dest.push(next_one),
+                None => return Ok(()),
+            }
+        }
+    }
+
+    // Position inside of the input of the next byte to read
+    pub fn get_pos(&self) -> usize {
+        self.pos
+    }
+
+    // Lenient modes give the '%' back as it is and continue right after it
+    fn broken_escape(&mut self, err: UrlDecodeError) -> Result<Option<u8>, UrlDecodeError> {
+        if self.mode == DecodeMode::Strict {
+            return Err(err);
+        }
+
+        self.pos = self.escape_start + 1;
+        self.state = UrlDecodeState::Normal(NormalState {});
+        Ok(Some(b'%'))
+    }
 }
@@ -0,0 +1,549 @@
+use std::{
+    borrow::Cow,
+    collections::{hash_map::Entry, HashMap},
+    fmt::Display,
+    str::FromStr,
+};
+
+use serde::de::{
+    value::StrDeserializer, DeserializeOwned, DeserializeSeed, EnumAccess, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+
+use crate::url_decoder::DecodeOptions;
+
+use super::{ReadingEncodedDataError, UrlEncodedDataReader, UrlEncodedValue};
+
+/// Deserializes query string or form body into the struct.
+/// Repeated keys and `key[]` are collected into sequences. The first value is taken for a single field
+pub fn from_str<T: DeserializeOwned>(src: &str) -> Result<T, ReadingEncodedDataError> {
+    let reader = UrlEncodedDataReader::new(src)?;
+    reader.deserialize()
+}
+
+pub struct UrlEncodedDeserializer<'r, 's> {
+    params: Vec<(&'r str, Vec<Option<&'s str>>)>,
+    options: DecodeOptions,
+}
+
+impl<'r, 's> UrlEncodedDeserializer<'r, 's> {
+    pub fn new(values: &'r [UrlEncodedValue<'s>]) -> Self {
+        Self::new_with_options(values, DecodeOptions::default())
+    }
+
+    pub fn new_with_options(values: &'r [UrlEncodedValue<'s>], options: DecodeOptions) -> Self {
+        let mut params: Vec<(&'r str, Vec<Option<&'s str>>)> = Vec::new();
+        // Keys keep the order they first appear in, the map only points to their place in params
+        let mut positions: HashMap<&'r str, usize> = HashMap::with_capacity(values.len());
+
+        for value in values {
+            let name = value.get_name();
+
+            match positions.entry(name) {
+                Entry::Occupied(entry) => params[*entry.get()].1.push(value.value),
+                Entry::Vacant(entry) => {
+                    entry.insert(params.len());
+                    params.push((name, vec![value.value]));
+                }
+            }
+        }
+
+        Self { params, options }
+    }
+}
+
+impl<'de, 'r> serde::Deserializer<'de> for UrlEncodedDeserializer<'r, 'de> {
+    type Error = ReadingEncodedDataError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(ParamsAccess {
+            params: self.params.into_iter(),
+            value: None,
+            options: self.options,
+        })
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf unit
+        unit_struct seq tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct ParamsAccess<'r, 's> {
+    params: std::vec::IntoIter<(&'r str, Vec<Option<&'s str>>)>,
+    value: Option<(&'r str, Vec<Option<&'s str>>)>,
+    options: DecodeOptions,
+}
+
+impl<'de, 'r> MapAccess<'de> for ParamsAccess<'r, 'de> {
+    type Error = ReadingEncodedDataError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((name, values)) = self.params.next() else {
+            return Ok(None);
+        };
+
+        self.value = Some((name, values));
+        seed.deserialize(StrDeserializer::new(name)).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let Some((name, values)) = self.value.take() else {
+            return Err(ReadingEncodedDataError::Custom(
+                "Value is requested before the key".to_string(),
+            ));
+        };
+
+        // Errors raised by serde itself (unknown variant, invalid type) do not know the field name
+        seed.deserialize(ValueDeserializer {
+            name,
+            values,
+            options: self.options,
+        })
+        .map_err(|err| match err {
+            ReadingEncodedDataError::Custom(reason) => ReadingEncodedDataError::InvalidValue {
+                name: name.to_string(),
+                reason,
+            },
+            err => err,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.params.len())
+    }
+}
+
+struct ValueDeserializer<'r, 's> {
+    name: &'r str,
+    values: Vec<Option<&'s str>>,
+    options: DecodeOptions,
+}
+
+impl<'r, 's> ValueDeserializer<'r, 's> {
+    fn decode(&self) -> Result<Cow<'s, str>, ReadingEncodedDataError> {
+        // Flag without a value (`?verbose`) is read as an empty string
+        let Some(value) = self.values[0] else {
+            return Ok(Cow::Borrowed(""));
+        };
+
+        if !self.options.needs_decoding(value) {
+            return Ok(Cow::Borrowed(value));
+        }
+
+        let result =
+            crate::url_decoder::decode_from_url_query_string_with_options(value, self.options)
+                .map_err(|err| self.invalid_value(err.to_string()))?;
+
+        Ok(Cow::Owned(result))
+    }
+
+    fn parse<T>(&self) -> Result<T, ReadingEncodedDataError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self.decode()?;
+
+        value
+            .parse()
+            .map_err(|err| self.invalid_value(format!("Can not parse '{}'. {}", value, err)))
+    }
+
+    fn invalid_value(&self, reason: impl Into<String>) -> ReadingEncodedDataError {
+        ReadingEncodedDataError::InvalidValue {
+            name: self.name.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl<'de, 'r> serde::Deserializer<'de> for ValueDeserializer<'r, 'de> {
+    type Error = ReadingEncodedDataError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.values.len() > 1 {
+            return self.deserialize_seq(visitor);
+        }
+
+        self.deserialize_str(visitor)
+    }
+
+    // `?verbose` turns the flag on
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.values[0].is_none() {
+            return visitor.visit_bool(true);
+        }
+
+        visitor.visit_bool(self.parse()?)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i8(self.parse()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i16(self.parse()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i32(self.parse()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i64(self.parse()?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u8(self.parse()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u16(self.parse()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u32(self.parse()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u64(self.parse()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_f32(self.parse()?)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_f64(self.parse()?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_char(self.parse()?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.decode()? {
+            Cow::Borrowed(value) => visitor.visit_borrowed_str(value),
+            Cow::Owned(value) => visitor.visit_string(value),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.decode()? {
+            Cow::Borrowed(value) => visitor.visit_borrowed_bytes(value.as_bytes()),
+            Cow::Owned(value) => visitor.visit_byte_buf(value.into_bytes()),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    // Param is present, but has no value: `?page=`
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.values.len() == 1 && self.values[0] == Some("") {
+            return visitor.visit_none();
+        }
+
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(ValuesAccess {
+            name: self.name,
+            values: self.values.into_iter(),
+            options: self.options,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(self.invalid_value("Nested structures are not supported"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(self.invalid_value("Nested structures are not supported"))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+// Enums are read by variant name, so only unit variants can be expressed by a single value
+impl<'de, 'r> EnumAccess<'de> for ValueDeserializer<'r, 'de> {
+    type Error = ReadingEncodedDataError;
+    type Variant = UnitVariantAccess<'r>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = self.decode()?;
+        let value = seed.deserialize(StrDeserializer::<ReadingEncodedDataError>::new(&variant))?;
+        Ok((value, UnitVariantAccess { name: self.name }))
+    }
+}
+
+struct UnitVariantAccess<'r> {
+    name: &'r str,
+}
+
+impl<'r> UnitVariantAccess<'r> {
+    fn unsupported(&self) -> ReadingEncodedDataError {
+        ReadingEncodedDataError::InvalidValue {
+            name: self.name.to_string(),
+            reason: "Only unit enum variants are supported".to_string(),
+        }
+    }
+}
+
+impl<'de, 'r> VariantAccess<'de> for UnitVariantAccess<'r> {
+    type Error = ReadingEncodedDataError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        _seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        Err(self.unsupported())
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(self.unsupported())
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(self.unsupported())
+    }
+}
+
+struct ValuesAccess<'r, 's> {
+    name: &'r str,
+    values: std::vec::IntoIter<Option<&'s str>>,
+    options: DecodeOptions,
+}
+
+impl<'de, 'r> SeqAccess<'de> for ValuesAccess<'r, 'de> {
+    type Error = ReadingEncodedDataError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        let Some(value) = self.values.next() else {
+            return Ok(None);
+        };
+
+        seed.deserialize(ValueDeserializer {
+            name: self.name,
+            values: vec![value],
+            options: self.options,
+        })
+        .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::Deserialize;
+
+    use crate::url_encoded_data_reader::{ReadingEncodedDataError, UrlEncodedDataReader};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Status {
+        Open,
+        Closed,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct SearchRequest {
+        #[serde(rename = "q")]
+        query: String,
+        page: Option<u32>,
+        #[serde(default)]
+        limit: u32,
+        ids: Vec<u64>,
+        status: Status,
+    }
+
+    #[test]
+    fn test_deserialize_struct() {
+        let result: SearchRequest =
+            super::from_str("q=John+Doe&ids[]=1&ids[]=2&status=closed&page=").unwrap();
+
+        assert_eq!(result.query, "John Doe");
+        assert_eq!(result.page, None);
+        assert_eq!(result.limit, 0);
+        assert_eq!(result.ids, vec![1, 2]);
+        assert_eq!(result.status, Status::Closed);
+
+        let result: SearchRequest =
+            super::from_str("q=a&page=3&limit=10&ids=5&status=open").unwrap();
+
+        assert_eq!(result.page, Some(3));
+        assert_eq!(result.limit, 10);
+        assert_eq!(result.ids, vec![5]);
+        assert_eq!(result.status, Status::Open);
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Flags {
+        #[serde(default)]
+        verbose: bool,
+        #[serde(default)]
+        dry_run: bool,
+    }
+
+    #[test]
+    fn test_flags() {
+        let result: Flags = super::from_str("verbose").unwrap();
+
+        assert!(result.verbose);
+        assert!(!result.dry_run);
+    }
+
+    #[test]
+    fn test_errors_name_the_field() {
+        let result = super::from_str::<SearchRequest>("ids=1&status=open");
+
+        match result.unwrap_err() {
+            ReadingEncodedDataError::RequiredParameterIsMissing(name) => assert_eq!(name, "q"),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        let result = super::from_str::<SearchRequest>("q=a&page=abc&status=open");
+
+        match result.unwrap_err() {
+            ReadingEncodedDataError::InvalidValue { name, .. } => assert_eq!(name, "page"),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        let result = super::from_str::<SearchRequest>("q=a&status=unknown");
+
+        match result.unwrap_err() {
+            ReadingEncodedDataError::InvalidValue { name, .. } => assert_eq!(name, "status"),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_borrowed_values() {
+        let reader = UrlEncodedDataReader::new("name=John&city=London").unwrap();
+
+        let result: BTreeMap<String, &str> = reader.deserialize().unwrap();
+
+        assert_eq!(result.get("name"), Some(&"John"));
+        assert_eq!(result.get("city"), Some(&"London"));
+    }
+
+    #[test]
+    fn test_interleaved_keys_in_big_form() {
+        let mut src = String::from("q=first");
+
+        for i in 0..1000 {
+            src.push_str(&format!("&ids={}&status=open", i));
+        }
+
+        let result: SearchRequest = super::from_str(&src).unwrap();
+
+        assert_eq!(result.query, "first");
+        assert_eq!(result.ids, (0..1000).collect::<Vec<u64>>());
+        assert_eq!(result.status, Status::Open);
+    }
+}
@@ -1,10 +1,16 @@
+use std::fmt::Display;
+
 use crate::url_decoder::UrlDecodeError;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ReadingEncodedDataError {
     RequiredParameterIsMissing(String),
     CanNotParseValue(String),
     UrlDecodeError(UrlDecodeError),
+    InvalidValue { name: String, reason: String },
+    MalformedKey { key: String, reason: String },
+    EmptyKey { pos: usize },
+    Custom(String),
 }
 
 impl From<UrlDecodeError> for ReadingEncodedDataError {
@@ -12,3 +18,42 @@ impl From<UrlDecodeError> for ReadingEncodedDataError {
         Self::UrlDecodeError(src)
     }
 }
+
+impl Display for ReadingEncodedDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RequiredParameterIsMissing(name) => {
+                write!(f, "Required parameter '{}' is missing", name)
+            }
+            Self::CanNotParseValue(value) => write!(f, "Can not parse value '{}'", value),
+            Self::UrlDecodeError(err) => write!(f, "Can not decode value. {}", err),
+            Self::InvalidValue { name, reason } => {
+                write!(f, "Invalid value of parameter '{}'. {}", name, reason)
+            }
+            Self::MalformedKey { key, reason } => {
+                write!(f, "Malformed key '{}'. {}", key, reason)
+            }
+            Self::EmptyKey { pos } => write!(f, "Empty key at position {}", pos),
+            Self::Custom(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ReadingEncodedDataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::UrlDecodeError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl serde::de::Error for ReadingEncodedDataError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self::RequiredParameterIsMissing(field.to_string())
+    }
+}
@@ -0,0 +1,52 @@
+use std::borrow::Cow;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum KeyLookupPolicy {
+    #[default]
+    Exact,
+    // partitionKey == PartitionKey == PARTITIONKEY
+    CaseInsensitive,
+    // partitionKey == PartitionKey == partition_key == partition-key
+    SnakeCamelInsensitive,
+}
+
+impl KeyLookupPolicy {
+    // Keys which are equal under the policy are normalized to the same string
+    pub fn normalize<'k>(&self, key: &'k str) -> Cow<'k, str> {
+        match self {
+            Self::Exact => Cow::Borrowed(key),
+            Self::CaseInsensitive => {
+                if key.bytes().any(|b| b.is_ascii_uppercase()) {
+                    Cow::Owned(key.to_ascii_lowercase())
+                } else {
+                    Cow::Borrowed(key)
+                }
+            }
+            Self::SnakeCamelInsensitive => Cow::Owned(
+                key.chars()
+                    .filter(|c| *c != '_' && *c != '-')
+                    .map(|c| c.to_ascii_lowercase())
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyLookupPolicy;
+
+    #[test]
+    fn test_normalize() {
+        let policy = KeyLookupPolicy::CaseInsensitive;
+        assert_eq!(policy.normalize("PartitionKey"), "partitionkey");
+        assert_eq!(policy.normalize("partition_key"), "partition_key");
+
+        let policy = KeyLookupPolicy::SnakeCamelInsensitive;
+        assert_eq!(policy.normalize("PartitionKey"), "partitionkey");
+        assert_eq!(policy.normalize("partition_key"), "partitionkey");
+        assert_eq!(policy.normalize("partition-key"), "partitionkey");
+
+        assert_eq!(KeyLookupPolicy::Exact.normalize("Key"), "Key");
+    }
+}
@@ -1,7 +1,13 @@
+mod deserializer;
 mod error;
+mod key_lookup_policy;
 mod url_encoded_data_reader;
+mod url_encoded_node;
 mod url_encoded_value;
 
+pub use deserializer::*;
 pub use error::*;
+pub use key_lookup_policy::*;
 pub use url_encoded_data_reader::*;
+pub use url_encoded_node::*;
 pub use url_encoded_value::*;
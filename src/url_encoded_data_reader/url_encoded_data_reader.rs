@@ -1,32 +1,112 @@
-use crate::url_decoder::UrlDecodeError;
+use std::{
+    borrow::Cow, cell::OnceCell, collections::HashMap, fmt::Display, ops::RangeInclusive,
+    str::FromStr,
+};
 
-use super::{ReadingEncodedDataError, UrlEncodedValue};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::Deserialize;
+
+use crate::url_decoder::{DecodeOptions, UrlDecodeError};
+
+use super::{
+    KeyLookupPolicy, ReadingEncodedDataError, UrlEncodedDeserializer, UrlEncodedNode,
+    UrlEncodedValue,
+};
+
+// Scanning a few params is cheaper than hashing all of them
+const INDEX_THRESHOLD: usize = 16;
 
 pub struct UrlEncodedDataReader<'s> {
     src: &'s str,
     query_string: Vec<UrlEncodedValue<'s>>,
+    options: DecodeOptions,
+    // How the requested names are matched against the keys
+    lookup_policy: KeyLookupPolicy,
+    // Normalized name -> positions inside of query_string. Small forms have no index
+    index: Option<HashMap<String, Vec<usize>>>,
+    // Built on the first request, since most of the readers never need it
+    tree: OnceCell<Result<UrlEncodedNode<'s>, ReadingEncodedDataError>>,
 }
 
 impl<'s> UrlEncodedDataReader<'s> {
     pub fn new(src: &'s str) -> Result<Self, UrlDecodeError> {
-        let mut query_string = Vec::new();
-        let elements = src.split("&");
+        Self::with_options(src, DecodeOptions::default())
+    }
 
-        for el in elements {
-            let kv = el.find('=');
+    // Rejects empty keys: `=value`, `a=1&&b=2`
+    pub fn new_strict(src: &'s str) -> Result<Self, ReadingEncodedDataError> {
+        Self::new_strict_with_options(src, DecodeOptions::default())
+    }
 
-            if let Some(index) = kv {
-                let key = crate::url_decoder::decode_from_url_query_string(&el[..index])?;
-                let value = UrlEncodedValue::new(key, &el[index + 1..]);
-                query_string.push(value);
-            }
-        }
+    pub fn with_options(src: &'s str, options: DecodeOptions) -> Result<Self, UrlDecodeError> {
+        Self::with_lookup_policy(src, options, KeyLookupPolicy::Exact)
+    }
 
-        let result = Self { query_string, src };
+    pub fn with_lookup_policy(
+        src: &'s str,
+        options: DecodeOptions,
+        lookup_policy: KeyLookupPolicy,
+    ) -> Result<Self, UrlDecodeError> {
+        let query_string = crate::query_string::parse_with_options(src, options)?;
+        let result = Self::create(src, query_string, options, lookup_policy);
 
         Ok(result)
     }
 
+    pub fn new_strict_with_options(
+        src: &'s str,
+        options: DecodeOptions,
+    ) -> Result<Self, ReadingEncodedDataError> {
+        let query_string = crate::query_string::parse_strict_with_options(src, options)?;
+        let result = Self::create(src, query_string, options, KeyLookupPolicy::Exact);
+
+        Ok(result)
+    }
+
+    fn create(
+        src: &'s str,
+        query_string: Vec<UrlEncodedValue<'s>>,
+        options: DecodeOptions,
+        lookup_policy: KeyLookupPolicy,
+    ) -> Self {
+        let index = if query_string.len() > INDEX_THRESHOLD {
+            Some(build_index(&query_string, lookup_policy))
+        } else {
+            None
+        };
+
+        Self {
+            src,
+            query_string,
+            options,
+            lookup_policy,
+            index,
+            tree: OnceCell::new(),
+        }
+    }
+
+    fn get_positions(&self, name: &str) -> Cow<'_, [usize]> {
+        let lookup_policy = self.lookup_policy;
+        let name = lookup_policy.normalize(name);
+
+        let Some(index) = &self.index else {
+            let positions = self
+                .query_string
+                .iter()
+                .enumerate()
+                .filter(|(_, itm)| lookup_policy.normalize(itm.get_name()) == name)
+                .map(|(position, _)| position)
+                .collect();
+
+            return Cow::Owned(positions);
+        };
+
+        match index.get(name.as_ref()) {
+            Some(positions) => Cow::Borrowed(positions),
+            None => Cow::Borrowed(&[]),
+        }
+    }
+
     pub fn get_required(
         &'s self,
         name: &str,
@@ -42,28 +122,133 @@ impl<'s> UrlEncodedDataReader<'s> {
     }
 
     pub fn get_optional(&'s self, name: &str) -> Option<UrlEncodedValue<'s>> {
-        for itm in &self.query_string {
-            if itm.get_name() == name {
-                return Some(itm.clone());
-            }
+        let position = *self.get_positions(name).first()?;
+        Some(self.query_string[position].clone())
+    }
+
+    // The first name from the list which is present wins: `&["pk", "partitionKey"]`
+    pub fn get_optional_any(&'s self, names: &[&str]) -> Option<UrlEncodedValue<'s>> {
+        names.iter().find_map(|name| self.get_optional(name))
+    }
+
+    // Missing param or param with an empty value gives the default one
+    pub fn get_or_default<T: FromStr>(
+        &'s self,
+        name: &str,
+        default: T,
+    ) -> Result<T, ReadingEncodedDataError> {
+        let Some(value) = self.get_optional(name) else {
+            return Ok(default);
+        };
+
+        if value.value.unwrap_or("").is_empty() {
+            return Ok(default);
+        }
+
+        value.parse_value()
+    }
+
+    // 1/0, true/false, on/off, yes/no. Flag without a value is true
+    pub fn get_bool(&'s self, name: &str) -> Result<bool, ReadingEncodedDataError> {
+        self.get_required(name)?.as_bool()
+    }
+
+    pub fn get_enum<E: FromStr>(&'s self, name: &str) -> Result<E, ReadingEncodedDataError> {
+        let value = self.get_required(name)?;
+        let as_string = value.as_string()?;
+
+        match as_string.parse::<E>() {
+            Ok(result) => Ok(result),
+            Err(_) => Err(value.invalid_value(format!("'{}' is not a valid value", as_string))),
         }
-        None
+    }
+
+    pub fn get_in_range<T: FromStr + PartialOrd + Display>(
+        &'s self,
+        name: &str,
+        range: RangeInclusive<T>,
+    ) -> Result<T, ReadingEncodedDataError> {
+        let value = self.get_required(name)?;
+        let result: T = value.parse_value()?;
+
+        if !range.contains(&result) {
+            return Err(value.invalid_value(format!(
+                "Value {} is out of range {}..={}",
+                result,
+                range.start(),
+                range.end()
+            )));
+        }
+
+        Ok(result)
+    }
+
+    pub fn get_date_time(
+        &'s self,
+        name: &str,
+    ) -> Result<DateTimeAsMicroseconds, ReadingEncodedDataError> {
+        self.get_required(name)?.as_date_time()
+    }
+
+    // Param is present without '=': `?verbose&dry_run`
+    pub fn has_flag(&self, name: &str) -> bool {
+        self.get_positions(name)
+            .iter()
+            .any(|position| !self.query_string[*position].has_value())
     }
 
     pub fn get_vec(&'s self, name: &str) -> Vec<UrlEncodedValue<'s>> {
-        let mut result = Vec::new();
-        for itm in &self.query_string {
-            if itm.get_name() == name {
-                result.push(itm.clone());
-            }
+        self.get_positions(name)
+            .iter()
+            .map(|position| self.query_string[*position].clone())
+            .collect()
+    }
+
+    /// Parses bracket keys (`filter[status]`, `items[0][id]`) into the tree. The tree is built once
+    pub fn get_tree(&self) -> Result<&UrlEncodedNode<'s>, ReadingEncodedDataError> {
+        let tree = self
+            .tree
+            .get_or_init(|| UrlEncodedNode::build(&self.query_string));
+
+        match tree {
+            Ok(tree) => Ok(tree),
+            Err(err) => Err(err.clone()),
         }
+    }
 
-        result
+    pub fn get_path(
+        &self,
+        path: &[&str],
+    ) -> Result<Option<&UrlEncodedNode<'s>>, ReadingEncodedDataError> {
+        let tree = self.get_tree()?;
+        Ok(tree.get_path(path))
     }
 
     pub fn get_raw(&self) -> &str {
         self.src
     }
+
+    /// Values which need no decoding are borrowed from the source string
+    pub fn deserialize<T: Deserialize<'s>>(&self) -> Result<T, ReadingEncodedDataError> {
+        T::deserialize(UrlEncodedDeserializer::new_with_options(
+            &self.query_string,
+            self.options,
+        ))
+    }
+}
+
+fn build_index(
+    query_string: &[UrlEncodedValue],
+    lookup_policy: KeyLookupPolicy,
+) -> HashMap<String, Vec<usize>> {
+    let mut result: HashMap<String, Vec<usize>> = HashMap::new();
+
+    for (position, itm) in query_string.iter().enumerate() {
+        let name = lookup_policy.normalize(itm.get_name()).into_owned();
+        result.entry(name).or_default().push(position);
+    }
+
+    result
 }
 
 #[cfg(test)]
@@ -149,4 +334,215 @@ mod tests {
 
         assert_eq!(0, result.len());
     }
+
+    #[test]
+    pub fn test_flags() {
+        let query_string = UrlEncodedDataReader::new("verbose&page=2&empty=").unwrap();
+
+        assert!(query_string.has_flag("verbose"));
+        assert!(!query_string.has_flag("page"));
+        assert!(!query_string.has_flag("empty"));
+        assert!(!query_string.has_flag("dry_run"));
+
+        let verbose = query_string.get_required("verbose").unwrap();
+        assert_eq!(verbose.value, None);
+        assert_eq!(verbose.as_string().unwrap(), "");
+
+        assert!(UrlEncodedDataReader::new_strict("verbose&page=2").is_ok());
+        assert!(UrlEncodedDataReader::new_strict("verbose&&page=2").is_err());
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum SortOrder {
+        Asc,
+        Desc,
+    }
+
+    impl FromStr for SortOrder {
+        type Err = ();
+
+        fn from_str(src: &str) -> Result<Self, Self::Err> {
+            match src {
+                "asc" => Ok(Self::Asc),
+                "desc" => Ok(Self::Desc),
+                _ => Err(()),
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_typed_getters() {
+        let query_string =
+            UrlEncodedDataReader::new("page=3&size=&active=on&deleted=0&flag&sort=desc&order=up")
+                .unwrap();
+
+        assert_eq!(query_string.get_or_default("page", 1).unwrap(), 3);
+        assert_eq!(query_string.get_or_default("size", 20).unwrap(), 20);
+        assert_eq!(query_string.get_or_default("limit", 100).unwrap(), 100);
+
+        assert!(query_string.get_bool("active").unwrap());
+        assert!(!query_string.get_bool("deleted").unwrap());
+        assert!(query_string.get_bool("flag").unwrap());
+
+        assert_eq!(
+            query_string.get_enum::<SortOrder>("sort").unwrap(),
+            SortOrder::Desc
+        );
+
+        assert_eq!(query_string.get_in_range("page", 1..=10).unwrap(), 3);
+    }
+
+    #[test]
+    pub fn test_typed_getters_errors() {
+        let query_string = UrlEncodedDataReader::new("page=30&active=maybe&order=up").unwrap();
+
+        for result in [
+            query_string.get_in_range("page", 1..=10).map(|_| ()),
+            query_string.get_bool("active").map(|_| ()),
+            query_string.get_enum::<SortOrder>("order").map(|_| ()),
+            query_string.get_date_time("active").map(|_| ()),
+        ] {
+            match result {
+                Err(ReadingEncodedDataError::InvalidValue { name, reason }) => {
+                    assert!(["page", "active", "order"].contains(&name.as_str()));
+                    assert!(!reason.is_empty());
+                }
+                _ => panic!("Unexpected result"),
+            }
+        }
+
+        match query_string.get_bool("missing") {
+            Err(ReadingEncodedDataError::RequiredParameterIsMissing(name)) => {
+                assert_eq!(name, "missing")
+            }
+            _ => panic!("Unexpected result"),
+        }
+    }
+
+    #[test]
+    pub fn test_lookup_policy() {
+        let src = "PartitionKey=1&row_key=2&row_key=3";
+
+        let query_string = UrlEncodedDataReader::new(src).unwrap();
+        assert!(query_string.get_optional("partitionKey").is_none());
+
+        let options = DecodeOptions::default();
+        let query_string = UrlEncodedDataReader::with_lookup_policy(
+            src,
+            options,
+            KeyLookupPolicy::CaseInsensitive,
+        )
+        .unwrap();
+        assert!(query_string.get_optional("partitionKey").is_some());
+        assert!(query_string.get_optional("rowKey").is_none());
+
+        let query_string = UrlEncodedDataReader::with_lookup_policy(
+            src,
+            options,
+            KeyLookupPolicy::SnakeCamelInsensitive,
+        )
+        .unwrap();
+        assert!(query_string.get_optional("partition_key").is_some());
+        assert_eq!(query_string.get_vec("RowKey").len(), 2);
+    }
+
+    #[test]
+    pub fn test_lookup_in_big_form() {
+        let src: Vec<String> = (0..INDEX_THRESHOLD + 4)
+            .map(|i| format!("Param_{}={}", i % 10, i))
+            .collect();
+        let src = src.join("&");
+
+        let query_string = UrlEncodedDataReader::with_lookup_policy(
+            &src,
+            DecodeOptions::default(),
+            KeyLookupPolicy::SnakeCamelInsensitive,
+        )
+        .unwrap();
+        assert!(query_string.index.is_some());
+
+        let values: Vec<String> = query_string
+            .get_vec("param1")
+            .iter()
+            .map(|itm| itm.as_string().unwrap())
+            .collect();
+        assert_eq!(values, vec!["1", "11"]);
+
+        assert!(query_string.get_optional("param_10").is_none());
+        assert!(!query_string.has_flag("param2"));
+    }
+
+    #[test]
+    pub fn test_get_optional_any() {
+        let query_string = UrlEncodedDataReader::new("partitionKey=1&pk=2").unwrap();
+
+        let result = query_string
+            .get_optional_any(&["pk", "partitionKey"])
+            .unwrap();
+        assert_eq!(result.value, Some("2"));
+
+        let result = query_string
+            .get_optional_any(&["partition_key", "partitionKey"])
+            .unwrap();
+        assert_eq!(result.value, Some("1"));
+
+        assert!(query_string.get_optional_any(&["a", "b"]).is_none());
+    }
+
+    #[test]
+    pub fn test_with_options() {
+        use crate::url_decoder::{DecodeOptions, PairSeparator};
+
+        let options = DecodeOptions::rfc3986().with_separator(PairSeparator::Both);
+        let query_string =
+            UrlEncodedDataReader::with_options("a=1+2;b=x%20y&c=3", options).unwrap();
+
+        let result = query_string.get_required("a").unwrap();
+        assert_eq!(result.as_string().unwrap(), "1+2");
+
+        let result = query_string.get_required("b").unwrap();
+        assert_eq!(result.as_string().unwrap(), "x y");
+
+        let result: u32 = query_string
+            .get_required("c")
+            .unwrap()
+            .parse_value()
+            .unwrap();
+        assert_eq!(result, 3);
+    }
+
+    #[test]
+    pub fn test_lossy_mode() {
+        use crate::url_decoder::{DecodeMode, DecodeOptions};
+
+        let src = "utm=50%&ref=%C3%28";
+
+        let query_string = UrlEncodedDataReader::new(src).unwrap();
+        assert!(query_string
+            .get_required("utm")
+            .unwrap()
+            .as_string()
+            .is_err());
+
+        let options = DecodeOptions::default().with_mode(DecodeMode::Lossy);
+        let query_string = UrlEncodedDataReader::with_options(src, options).unwrap();
+
+        let result = query_string.get_required("utm").unwrap();
+        assert_eq!(result.as_string().unwrap(), "50%");
+
+        let result = query_string.get_required("ref").unwrap();
+        assert_eq!(result.as_string().unwrap(), "\u{FFFD}(");
+    }
+
+    #[test]
+    pub fn test_as_bytes() {
+        let query_string = UrlEncodedDataReader::new("sig=%8A%00%FF&plain=abc").unwrap();
+
+        let result = query_string.get_required("sig").unwrap();
+        assert_eq!(result.as_bytes().unwrap().as_ref(), &[0x8A, 0x00, 0xFF]);
+        assert!(result.as_string().is_err());
+
+        let result = query_string.get_required("plain").unwrap();
+        assert_eq!(result.as_bytes().unwrap().as_ref(), b"abc");
+    }
 }
@@ -0,0 +1,342 @@
+use super::{ReadingEncodedDataError, UrlEncodedValue};
+
+// PHP/Rails/qs style keys: filter[status]=open&filter[tags][]=a&items[0][id]=5
+#[derive(Clone)]
+pub enum UrlEncodedNode<'s> {
+    Value(UrlEncodedValue<'s>),
+    Array(Vec<UrlEncodedNode<'s>>),
+    Map(Vec<(String, UrlEncodedNode<'s>)>),
+}
+
+impl<'s> UrlEncodedNode<'s> {
+    // Builds the tree of all the params. Repeated keys become arrays
+    pub fn build(values: &[UrlEncodedValue<'s>]) -> Result<Self, ReadingEncodedDataError> {
+        let mut root = NodeBuilder::Map(Vec::new());
+
+        for value in values {
+            let key = value.get_key();
+
+            let result = parse_key(key).and_then(|segments| insert(&mut root, &segments, value));
+
+            if let Err(reason) = result {
+                return Err(ReadingEncodedDataError::MalformedKey {
+                    key: key.to_string(),
+                    reason: reason.to_string(),
+                });
+            }
+        }
+
+        Ok(root.build())
+    }
+
+    // Array items are addressed by their position: `&["items", "0", "id"]`
+    pub fn get_path(&self, path: &[&str]) -> Option<&Self> {
+        let mut result = self;
+
+        for segment in path {
+            result = result.get(segment)?;
+        }
+
+        Some(result)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Value(_) => None,
+            Self::Array(items) => items.get(key.parse::<usize>().ok()?),
+            Self::Map(items) => items
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, node)| node),
+        }
+    }
+
+    pub fn as_value(&self) -> Option<&UrlEncodedValue<'s>> {
+        match self {
+            Self::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[UrlEncodedNode<'s>]> {
+        match self {
+            Self::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+enum KeySegment<'k> {
+    Key(&'k str),
+    Index(usize),
+    Push,
+}
+
+fn parse_key(key: &str) -> Result<Vec<KeySegment<'_>>, &'static str> {
+    let Some(bracket_index) = key.find('[') else {
+        return Ok(vec![KeySegment::Key(key)]);
+    };
+
+    let name = &key[..bracket_index];
+
+    if name.is_empty() {
+        return Err("Key has no name before '['");
+    }
+
+    if name.contains(']') {
+        return Err("Unexpected ']'");
+    }
+
+    let mut result = vec![KeySegment::Key(name)];
+    let mut rest = &key[bracket_index..];
+
+    while !rest.is_empty() {
+        let Some(inner) = rest.strip_prefix('[') else {
+            return Err("Unexpected symbols after ']'");
+        };
+
+        let Some(end) = inner.find(']') else {
+            return Err("'[' is not closed");
+        };
+
+        let segment = &inner[..end];
+
+        if segment.contains('[') {
+            return Err("Unexpected '[' inside of brackets");
+        }
+
+        if segment.is_empty() {
+            result.push(KeySegment::Push);
+        } else if segment.bytes().all(|b| b.is_ascii_digit()) {
+            match segment.parse() {
+                Ok(index) => result.push(KeySegment::Index(index)),
+                Err(_) => return Err("Array index is too big"),
+            }
+        } else {
+            result.push(KeySegment::Key(segment));
+        }
+
+        rest = &inner[end + 1..];
+    }
+
+    Ok(result)
+}
+
+// Arrays keep the indices until the whole tree is built, so items[1]=b&items[0]=a ends up ordered.
+// next_index is where the next items[]= goes, so a push does not have to look through the items
+enum NodeBuilder<'s> {
+    Value(UrlEncodedValue<'s>),
+    Array {
+        items: Vec<(usize, NodeBuilder<'s>)>,
+        next_index: usize,
+    },
+    Map(Vec<(String, NodeBuilder<'s>)>),
+}
+
+impl<'s> NodeBuilder<'s> {
+    fn build(self) -> UrlEncodedNode<'s> {
+        match self {
+            Self::Value(value) => UrlEncodedNode::Value(value),
+            Self::Array { mut items, .. } => {
+                items.sort_by_key(|(index, _)| *index);
+                UrlEncodedNode::Array(items.into_iter().map(|(_, node)| node.build()).collect())
+            }
+            Self::Map(items) => UrlEncodedNode::Map(
+                items
+                    .into_iter()
+                    .map(|(name, node)| (name, node.build()))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn new_array() -> Self {
+        Self::Array {
+            items: Vec::new(),
+            next_index: 0,
+        }
+    }
+}
+
+fn push_item<'s>(
+    items: &mut Vec<(usize, NodeBuilder<'s>)>,
+    next_index: &mut usize,
+    index: usize,
+    node: NodeBuilder<'s>,
+) {
+    items.push((index, node));
+
+    if index >= *next_index {
+        *next_index = index + 1;
+    }
+}
+
+fn insert<'s>(
+    node: &mut NodeBuilder<'s>,
+    segments: &[KeySegment],
+    value: &UrlEncodedValue<'s>,
+) -> Result<(), &'static str> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return Ok(());
+    };
+
+    let child = match node {
+        NodeBuilder::Value(_) => return Err("Key is used both as a value and as an object"),
+        NodeBuilder::Map(items) => {
+            let KeySegment::Key(key) = segment else {
+                return Err("Key is used both as an array and as an object");
+            };
+
+            match items.iter().position(|(name, _)| name == key) {
+                Some(index) => &mut items[index].1,
+                None => {
+                    items.push((key.to_string(), create_node(rest, value)?));
+                    return Ok(());
+                }
+            }
+        }
+        NodeBuilder::Array { items, next_index } => match segment {
+            KeySegment::Key(_) => return Err("Key is used both as an array and as an object"),
+            KeySegment::Push => {
+                let index = *next_index;
+                push_item(items, next_index, index, create_node(rest, value)?);
+                return Ok(());
+            }
+            KeySegment::Index(index) => match items.iter().position(|(i, _)| i == index) {
+                Some(position) => &mut items[position].1,
+                None => {
+                    push_item(items, next_index, *index, create_node(rest, value)?);
+                    return Ok(());
+                }
+            },
+        },
+    };
+
+    if !rest.is_empty() {
+        return insert(child, rest, value);
+    }
+
+    match child {
+        NodeBuilder::Value(first) => {
+            let first = first.clone();
+
+            *child = NodeBuilder::Array {
+                items: vec![
+                    (0, NodeBuilder::Value(first)),
+                    (1, NodeBuilder::Value(value.clone())),
+                ],
+                next_index: 2,
+            };
+        }
+        NodeBuilder::Array { items, next_index } => {
+            let index = *next_index;
+            push_item(items, next_index, index, NodeBuilder::Value(value.clone()));
+        }
+        NodeBuilder::Map(_) => return Err("Key is used both as a value and as an object"),
+    }
+
+    Ok(())
+}
+
+fn create_node<'s>(
+    segments: &[KeySegment],
+    value: &UrlEncodedValue<'s>,
+) -> Result<NodeBuilder<'s>, &'static str> {
+    let mut result = match segments.first() {
+        None => return Ok(NodeBuilder::Value(value.clone())),
+        Some(KeySegment::Key(_)) => NodeBuilder::Map(Vec::new()),
+        Some(_) => NodeBuilder::new_array(),
+    };
+
+    insert(&mut result, segments, value)?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::url_encoded_data_reader::{ReadingEncodedDataError, UrlEncodedDataReader};
+
+    #[test]
+    fn test_nested_keys() {
+        let reader = UrlEncodedDataReader::new(
+            "filter[status]=open&filter[tags][]=a&filter[tags][]=b&items[1][id]=7&items[0][id]=5&items[0][name]=x",
+        )
+        .unwrap();
+
+        let tree = reader.get_tree().unwrap();
+
+        let status = tree.get_path(&["filter", "status"]).unwrap();
+        assert_eq!(status.as_value().unwrap().value, Some("open"));
+
+        let tags = tree
+            .get_path(&["filter", "tags"])
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[1].as_value().unwrap().value, Some("b"));
+
+        let id = tree.get_path(&["items", "0", "id"]).unwrap();
+        assert_eq!(id.as_value().unwrap().value, Some("5"));
+
+        let id = tree.get_path(&["items", "1", "id"]).unwrap();
+        assert_eq!(id.as_value().unwrap().value, Some("7"));
+
+        assert!(tree.get_path(&["items", "2"]).is_none());
+        assert!(tree.get_path(&["filter", "status", "x"]).is_none());
+    }
+
+    #[test]
+    fn test_repeated_keys_become_array() {
+        let reader = UrlEncodedDataReader::new("a=1&a=2&b=3").unwrap();
+
+        let a = reader.get_path(&["a"]).unwrap().unwrap();
+        assert_eq!(a.as_array().unwrap().len(), 2);
+
+        let b = reader.get_path(&["b"]).unwrap().unwrap();
+        assert_eq!(b.as_value().unwrap().value, Some("3"));
+
+        // The tree is built on the first lookup and reused after that
+        assert!(std::ptr::eq(
+            reader.get_tree().unwrap(),
+            reader.get_tree().unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_push_after_index() {
+        let reader =
+            UrlEncodedDataReader::new("items[3]=a&items[]=b&items[1]=c&items[]=d").unwrap();
+
+        let items = reader.get_path(&["items"]).unwrap().unwrap();
+        let items: Vec<_> = items
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item.as_value().unwrap().value.unwrap())
+            .collect();
+
+        // Pushes go after the biggest index seen so far
+        assert_eq!(items, vec!["c", "a", "b", "d"]);
+    }
+
+    #[test]
+    fn test_malformed_keys() {
+        for query in [
+            "a[b=1",
+            "[a]=1",
+            "a[b]c=1",
+            "a[[b]]=1",
+            "a=1&a[b]=2",
+            "a[b]=1&a[]=2",
+        ] {
+            let reader = UrlEncodedDataReader::new(query).unwrap();
+
+            match reader.get_tree() {
+                Err(ReadingEncodedDataError::MalformedKey { .. }) => {}
+                _ => panic!("Query '{}' must fail", query),
+            }
+        }
+    }
+}
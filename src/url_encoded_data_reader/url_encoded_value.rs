@@ -1,18 +1,34 @@
-use std::str::FromStr;
+use std::{borrow::Cow, str::FromStr};
 
-use rust_extensions::StrOrString;
+use rust_extensions::{date_time::DateTimeAsMicroseconds, StrOrString};
+
+use crate::url_decoder::DecodeOptions;
 
 use super::ReadingEncodedDataError;
 
 #[derive(Clone)]
 pub struct UrlEncodedValue<'s> {
     name: String,
-    pub value: &'s str,
+    // None for the flag-style param without '=': `?verbose`
+    pub value: Option<&'s str>,
+    options: DecodeOptions,
 }
 
 impl<'s> UrlEncodedValue<'s> {
-    pub fn new(name: String, value: &'s str) -> Self {
-        Self { name, value }
+    pub fn new(name: String, value: Option<&'s str>) -> Self {
+        Self::new_with_options(name, value, DecodeOptions::default())
+    }
+
+    pub fn new_with_options(name: String, value: Option<&'s str>, options: DecodeOptions) -> Self {
+        Self {
+            name,
+            value,
+            options,
+        }
+    }
+
+    pub fn has_value(&self) -> bool {
+        self.value.is_some()
     }
 
     pub fn get_name(&self) -> &str {
@@ -23,23 +39,87 @@ impl<'s> UrlEncodedValue<'s> {
         &self.name
     }
 
+    // Decoded key as it came, brackets included
+    pub fn get_key(&self) -> &str {
+        &self.name
+    }
+
+    // Flag without a value is read as an empty string
     pub fn as_string(&self) -> Result<String, ReadingEncodedDataError> {
-        let result = crate::url_decoder::decode_from_url_query_string(self.value)?;
+        let result = crate::url_decoder::decode_from_url_query_string_with_options(
+            self.get_raw_value(),
+            self.options,
+        )?;
         Ok(result)
     }
 
     pub fn as_str_or_string(&'s self) -> Result<StrOrString<'s>, ReadingEncodedDataError> {
-        let result = crate::url_decoder::decode_as_str_or_string(self.value)?;
+        let result = crate::url_decoder::decode_as_str_or_string_with_options(
+            self.get_raw_value(),
+            self.options,
+        )?;
+        Ok(result)
+    }
+
+    // Decoded value without UTF-8 validation
+    pub fn as_bytes(&self) -> Result<Cow<'s, [u8]>, ReadingEncodedDataError> {
+        let result =
+            crate::url_decoder::decode_to_bytes_with_options(self.get_raw_value(), self.options)?;
         Ok(result)
     }
 
     pub fn parse<T: FromStr>(&'s self) -> Result<T, ReadingEncodedDataError> {
-        let result = self.value.parse::<T>();
+        let result = self.get_raw_value().parse::<T>();
         return match result {
             Ok(value) => Ok(value),
             _ => Err(ReadingEncodedDataError::CanNotParseValue(
-                self.value.to_string(),
+                self.get_raw_value().to_string(),
             )),
         };
     }
+
+    // Same as parse, but the value is decoded first and the error carries the field name
+    pub fn parse_value<T: FromStr>(&self) -> Result<T, ReadingEncodedDataError> {
+        let value = self.as_string()?;
+
+        match value.parse::<T>() {
+            Ok(result) => Ok(result),
+            Err(_) => Err(self.invalid_value(format!("Can not parse value '{}'", value))),
+        }
+    }
+
+    // Flag without a value (`?verbose`) is true
+    pub fn as_bool(&self) -> Result<bool, ReadingEncodedDataError> {
+        if !self.has_value() {
+            return Ok(true);
+        }
+
+        let value = self.as_string()?;
+
+        match value.to_lowercase().as_str() {
+            "1" | "true" | "on" | "yes" => Ok(true),
+            "0" | "false" | "off" | "no" => Ok(false),
+            _ => Err(self.invalid_value(format!("Can not convert '{}' to boolean", value))),
+        }
+    }
+
+    pub fn as_date_time(&self) -> Result<DateTimeAsMicroseconds, ReadingEncodedDataError> {
+        let value = self.as_string()?;
+
+        match DateTimeAsMicroseconds::from_str(&value) {
+            Some(result) => Ok(result),
+            None => Err(self.invalid_value(format!("Can not convert '{}' to DateTime", value))),
+        }
+    }
+
+    pub fn invalid_value(&self, reason: String) -> ReadingEncodedDataError {
+        ReadingEncodedDataError::InvalidValue {
+            name: self.get_name().to_string(),
+            reason,
+        }
+    }
+
+    fn get_raw_value(&self) -> &'s str {
+        self.value.unwrap_or("")
+    }
 }
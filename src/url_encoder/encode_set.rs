@@ -0,0 +1,110 @@
+// Which bytes are left as they are. Everything else is written as %XX.
+// Non-ASCII symbols are always encoded byte by byte of their UTF-8 representation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeSet {
+    // Single path segment: '/' is encoded
+    PathSegment,
+    // Whole path: '/' is kept
+    Path,
+    // '=' is encoded, so the key does not run into the value
+    QueryKey,
+    QueryValue,
+    Fragment,
+    // ':' is encoded, since it separates the username from the password
+    UserInfo,
+    // WHATWG application/x-www-form-urlencoded: only alphanumerics and `*-._` are kept
+    FormUrlEncoded,
+    // RFC 3986 unreserved symbols only: alphanumerics and `-._~`
+    Unreserved,
+}
+
+impl EncodeSet {
+    pub fn must_encode(&self, b: u8) -> bool {
+        self.get_table()[b as usize]
+    }
+
+    pub(crate) fn get_table(&self) -> &'static [bool; 256] {
+        match self {
+            Self::PathSegment => &PATH_SEGMENT,
+            Self::Path => &PATH,
+            Self::QueryKey => &QUERY_KEY,
+            Self::QueryValue => &QUERY_VALUE,
+            Self::Fragment => &FRAGMENT,
+            Self::UserInfo => &USER_INFO,
+            Self::FormUrlEncoded => &FORM_URL_ENCODED,
+            Self::Unreserved => &UNRESERVED,
+        }
+    }
+
+    // Query components follow the form convention, so a space becomes '+'
+    pub fn space_as_plus(&self) -> bool {
+        matches!(
+            self,
+            Self::QueryKey | Self::QueryValue | Self::FormUrlEncoded
+        )
+    }
+}
+
+// One lookup per byte instead of the rules below. Tables are built at compile time
+static PATH_SEGMENT: [bool; 256] = build_table(EncodeSet::PathSegment);
+static PATH: [bool; 256] = build_table(EncodeSet::Path);
+static QUERY_KEY: [bool; 256] = build_table(EncodeSet::QueryKey);
+static QUERY_VALUE: [bool; 256] = build_table(EncodeSet::QueryValue);
+static FRAGMENT: [bool; 256] = build_table(EncodeSet::Fragment);
+static USER_INFO: [bool; 256] = build_table(EncodeSet::UserInfo);
+static FORM_URL_ENCODED: [bool; 256] = build_table(EncodeSet::FormUrlEncoded);
+static UNRESERVED: [bool; 256] = build_table(EncodeSet::Unreserved);
+
+const fn build_table(set: EncodeSet) -> [bool; 256] {
+    let mut result = [true; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        result[i] = must_encode(set, i as u8);
+        i += 1;
+    }
+
+    result
+}
+
+const fn must_encode(set: EncodeSet, b: u8) -> bool {
+    if b.is_ascii_alphanumeric() {
+        return false;
+    }
+
+    match set {
+        EncodeSet::Unreserved => !matches!(b, b'-' | b'.' | b'_' | b'~'),
+        EncodeSet::FormUrlEncoded => !matches!(b, b'*' | b'-' | b'.' | b'_'),
+        EncodeSet::PathSegment => !is_pchar(b),
+        EncodeSet::Path => !is_pchar(b) && b != b'/',
+        EncodeSet::QueryKey => !is_query_char(b) || b == b'=',
+        EncodeSet::QueryValue => !is_query_char(b),
+        EncodeSet::Fragment => !is_pchar(b) && b != b'/' && b != b'?',
+        // '+' is kept encoded, since a lot of parsers read it as a space
+        EncodeSet::UserInfo => b == b'+' || !(is_unreserved(b) || is_sub_delim(b)),
+    }
+}
+
+const fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~')
+}
+
+const fn is_sub_delim(b: u8) -> bool {
+    matches!(
+        b,
+        b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'='
+    )
+}
+
+const fn is_pchar(b: u8) -> bool {
+    is_unreserved(b) || is_sub_delim(b) || b == b':' || b == b'@'
+}
+
+// '&' and ';' separate pairs and '+' is read as a space, so they can not be left as they are
+const fn is_query_char(b: u8) -> bool {
+    if matches!(b, b'&' | b';' | b'+') {
+        return false;
+    }
+
+    is_pchar(b) || b == b'/' || b == b'?'
+}
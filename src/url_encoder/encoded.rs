@@ -0,0 +1,58 @@
+use std::fmt::{Display, Write};
+
+use super::EncodeSet;
+
+// Encodes while formatting, so `write!(dest, "q={}", Encoded(value))` does not allocate.
+// Uses the same application/x-www-form-urlencoded rules as encode_string
+pub struct Encoded<'s>(pub &'s str);
+
+impl<'s> Display for Encoded<'s> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let set = EncodeSet::FormUrlEncoded;
+        let mut safe_from = 0;
+
+        for (index, b) in self.0.bytes().enumerate() {
+            let space_as_plus = b == b' ' && set.space_as_plus();
+
+            if !space_as_plus && !set.must_encode(b) {
+                continue;
+            }
+
+            // Safe bytes are ASCII, so a non-empty run of them always starts on a char boundary
+            if safe_from < index {
+                f.write_str(&self.0[safe_from..index])?;
+            }
+
+            safe_from = index + 1;
+
+            if space_as_plus {
+                f.write_char('+')?;
+            } else {
+                write!(f, "%{:02X}", b)?;
+            }
+        }
+
+        if safe_from < self.0.len() {
+            f.write_str(&self.0[safe_from..])?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Encoded;
+
+    #[test]
+    fn test_encoded() {
+        for src in ["", "plain", "a b&c=d", "café/ж", "100%"] {
+            assert_eq!(
+                Encoded(src).to_string(),
+                crate::url_encoder::encode_string(src).as_str()
+            );
+        }
+
+        assert_eq!(format!("q={}&page=1", Encoded("a b")), "q=a+b&page=1");
+    }
+}
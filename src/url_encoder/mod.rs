@@ -1,3 +1,8 @@
-pub mod encode_map;
+mod encode_set;
+pub use encode_set::*;
+mod encoded;
+pub use encoded::*;
+mod url_encode_writer;
+pub use url_encode_writer::*;
 mod url_encoder;
 pub use url_encoder::*;
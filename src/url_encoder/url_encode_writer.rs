@@ -0,0 +1,112 @@
+use std::io::Write;
+
+use super::EncodeSet;
+
+// Every byte takes at most 3 bytes once encoded
+const BUFFER_SIZE: usize = 1024;
+
+// Encodes everything written into it. Separators ('=' and '&') must stay as they are,
+// so they are written straight into get_mut()
+pub struct UrlEncodeWriter<W: Write> {
+    inner: W,
+    set: EncodeSet,
+}
+
+impl<W: Write> UrlEncodeWriter<W> {
+    // application/x-www-form-urlencoded, the same as encode_string
+    pub fn new(inner: W) -> Self {
+        Self::with_set(inner, EncodeSet::FormUrlEncoded)
+    }
+
+    pub fn with_set(inner: W, set: EncodeSet) -> Self {
+        Self { inner, set }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for UrlEncodeWriter<W> {
+    // Bytes are encoded one by one, so a UTF-8 symbol may be split between the calls.
+    // One call encodes as much as fits into the buffer and reports how many bytes it took.
+    // The encoded chunk is written whole, since a part of an escape can not be given back
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut buffer = [0u8; BUFFER_SIZE];
+        let mut len = 0;
+        let mut consumed = 0;
+
+        for b in buf {
+            if len + 3 > BUFFER_SIZE {
+                break;
+            }
+
+            if *b == b' ' && self.set.space_as_plus() {
+                buffer[len] = b'+';
+                len += 1;
+            } else if self.set.must_encode(*b) {
+                buffer[len..len + 3].copy_from_slice(&super::percent_escape(*b));
+                len += 3;
+            } else {
+                buffer[len] = *b;
+                len += 1;
+            }
+
+            consumed += 1;
+        }
+
+        self.inner.write_all(&buffer[..len])?;
+        Ok(consumed)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::UrlEncodeWriter;
+
+    #[test]
+    fn test_writer() {
+        let mut writer = UrlEncodeWriter::new(Vec::new());
+
+        writer.write_all(b"name").unwrap();
+        writer.get_mut().write_all(b"=").unwrap();
+
+        // 'ж' is split between two writes
+        let value = "a b&ж".as_bytes();
+        writer.write_all(&value[..5]).unwrap();
+        writer.write_all(&value[5..]).unwrap();
+
+        let result = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(result, "name=a+b%26%D0%B6");
+    }
+
+    #[test]
+    fn test_big_write() {
+        let src = "a b/ж".repeat(1000);
+
+        let mut writer = UrlEncodeWriter::new(Vec::new());
+
+        // A single call takes no more than fits into the buffer
+        let written = writer.write(src.as_bytes()).unwrap();
+        assert!(written < src.len());
+
+        writer.write_all(&src.as_bytes()[written..]).unwrap();
+
+        let result = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(result, crate::url_encoder::encode_string(&src).as_str());
+    }
+}
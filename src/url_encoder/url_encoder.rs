@@ -1,38 +1,124 @@
 use rust_extensions::StrOrString;
 
-pub fn encode_string<'s>(src: &'s str) -> StrOrString<'s> {
-    let as_bytes = src.as_bytes();
-    if !has_to_be_encoded(as_bytes) {
+use super::EncodeSet;
+
+// application/x-www-form-urlencoded: space is '+', every byte outside of `[A-Za-z0-9*-._]` is %XX
+pub fn encode_string(src: &str) -> StrOrString<'_> {
+    encode_with(EncodeSet::FormUrlEncoded, src)
+}
+
+pub fn encode_with(set: EncodeSet, src: &str) -> StrOrString<'_> {
+    let Some(first) = find_to_encode(set, src.as_bytes()) else {
         return StrOrString::create_as_str(src);
-    }
+    };
+
+    let mut result = String::with_capacity(src.len() + (src.len() - first) * 2);
+    result.push_str(&src[..first]);
+    encode_bytes_and_copy(&mut result, set, &src.as_bytes()[first..]);
+    StrOrString::create_as_string(result)
+}
 
-    let mut result = String::new();
+pub fn encode_with_and_copy(dest: &mut String, set: EncodeSet, src: &str) {
+    encode_bytes_and_copy(dest, set, src.as_bytes());
+}
+
+// Binary payloads are encoded with the form set, so any byte sequence survives
+pub fn encode_bytes(src: &[u8]) -> StrOrString<'_> {
+    let set = EncodeSet::FormUrlEncoded;
 
-    for i in 0..as_bytes.len() {
-        let b = as_bytes[i] as char;
-        if let Some(str) = super::encode_map::URL_ENCODE_SYMBOLS.get(&b) {
-            result.push_str(str);
-        } else {
-            result.push(b)
+    if find_to_encode(set, src).is_none() {
+        // Nothing to encode means it is plain ASCII
+        if let Ok(result) = std::str::from_utf8(src) {
+            return StrOrString::create_as_str(result);
         }
     }
 
+    let mut result = String::with_capacity(src.len() * 3);
+    encode_bytes_and_copy(&mut result, set, src);
     StrOrString::create_as_string(result)
 }
 
-fn has_to_be_encoded(src: &[u8]) -> bool {
-    for i in 0..src.len() {
-        let b = src[i] as char;
-        if super::encode_map::URL_ENCODE_SYMBOLS.contains_key(&b) {
-            return true;
+// Runs of safe bytes are copied at once. Multi-byte UTF-8 symbols are never safe,
+// so every run is plain ASCII. Bytes to encode come in runs too (every byte of a UTF-8 symbol),
+// so a run is encoded before looking for the next one
+fn encode_bytes_and_copy(dest: &mut String, set: EncodeSet, mut src: &[u8]) {
+    while let Some(index) = find_to_encode(set, src) {
+        push_ascii(dest, &src[..index]);
+        src = &src[index..];
+
+        let run = src
+            .iter()
+            .position(|b| !set.must_encode(*b))
+            .unwrap_or(src.len());
+
+        for b in &src[..run] {
+            if *b == b' ' && set.space_as_plus() {
+                dest.push('+');
+            } else {
+                push_escaped(dest, *b);
+            }
+        }
+
+        src = &src[run..];
+    }
+
+    push_ascii(dest, src);
+}
+
+// Bytes to check at once
+const WORD_SIZE: usize = 8;
+
+// Safe runs are skipped a word at a time: the lookups of a word are gathered into a bit mask
+// with no branch in between, and the lowest bit set is the byte to encode
+fn find_to_encode(set: EncodeSet, src: &[u8]) -> Option<usize> {
+    let table = set.get_table();
+    let mut words = src.chunks_exact(WORD_SIZE);
+    let mut offset = 0;
+
+    for word in &mut words {
+        let mask = word
+            .iter()
+            .enumerate()
+            .fold(0u8, |mask, (i, b)| mask | ((table[*b as usize] as u8) << i));
+
+        if mask != 0 {
+            return Some(offset + mask.trailing_zeros() as usize);
         }
+
+        offset += WORD_SIZE;
     }
 
-    false
+    words
+        .remainder()
+        .iter()
+        .position(|b| table[*b as usize])
+        .map(|index| offset + index)
+}
+
+// Safe bytes are ASCII in every encode set, so a run of them is always valid UTF-8
+fn push_ascii(dest: &mut String, src: &[u8]) {
+    debug_assert!(src.is_ascii());
+    dest.push_str(std::str::from_utf8(src).expect("Safe bytes must be ASCII"));
+}
+
+fn push_escaped(dest: &mut String, b: u8) {
+    for c in percent_escape(b) {
+        dest.push(c as char);
+    }
+}
+
+// 0x2F -> `%2F`
+pub fn percent_escape(b: u8) -> [u8; 3] {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    [b'%', HEX[(b >> 4) as usize], HEX[(b & 0x0F) as usize]]
 }
 
 #[cfg(test)]
 mod test_encodes {
+    use proptest::prelude::*;
+
+    use crate::url_encoder::EncodeSet;
 
     #[test]
     fn test() {
@@ -42,4 +128,102 @@ mod test_encodes {
 
         assert_eq!("value1%7Cvalue2", result.as_str());
     }
+
+    #[test]
+    fn test_encode_bytes() {
+        let src = [0x00, 0xFF, b'a', b' ', b'/', b'\r', 0xC3];
+
+        let result = super::encode_bytes(&src);
+        assert_eq!("%00%FFa+%2F%0D%C3", result.as_str());
+
+        let decoded = crate::url_decoder::decode_to_bytes(result.as_str()).unwrap();
+        assert_eq!(decoded.as_ref(), &src);
+
+        assert_eq!("abc", super::encode_bytes(b"abc").as_str());
+    }
+
+    #[test]
+    fn test_encode_with() {
+        let src = "a b/c?d=e&f+g#ж~";
+
+        let cases = [
+            (EncodeSet::PathSegment, "a%20b%2Fc%3Fd=e&f+g%23%D0%B6~"),
+            (EncodeSet::Path, "a%20b/c%3Fd=e&f+g%23%D0%B6~"),
+            (EncodeSet::QueryKey, "a+b/c?d%3De%26f%2Bg%23%D0%B6~"),
+            (EncodeSet::QueryValue, "a+b/c?d=e%26f%2Bg%23%D0%B6~"),
+            (EncodeSet::Fragment, "a%20b/c?d=e&f+g%23%D0%B6~"),
+            (EncodeSet::UserInfo, "a%20b%2Fc%3Fd=e&f%2Bg%23%D0%B6~"),
+            (
+                EncodeSet::FormUrlEncoded,
+                "a+b%2Fc%3Fd%3De%26f%2Bg%23%D0%B6%7E",
+            ),
+            (EncodeSet::Unreserved, "a%20b%2Fc%3Fd%3De%26f%2Bg%23%D0%B6~"),
+        ];
+
+        for (set, expected) in cases {
+            assert_eq!(expected, super::encode_with(set, src).as_str(), "{:?}", set);
+        }
+
+        assert_eq!("%0D%0A", super::encode_string("\r\n").as_str());
+        assert_eq!(
+            "plain",
+            super::encode_with(EncodeSet::Unreserved, "plain").as_str()
+        );
+    }
+
+    #[test]
+    fn test_non_ascii_and_unsafe_symbols() {
+        assert_eq!("caf%C3%A9", super::encode_string("café").as_str());
+        assert_eq!(
+            "%22%3C%3E%60%7B%7D%7F%01",
+            super::encode_string("\"<>`{}\u{7F}\u{1}").as_str()
+        );
+    }
+
+    // decode(encode(s)) == s for random strings over the whole Unicode range
+    const SETS: [EncodeSet; 8] = [
+        EncodeSet::PathSegment,
+        EncodeSet::Path,
+        EncodeSet::QueryKey,
+        EncodeSet::QueryValue,
+        EncodeSet::Fragment,
+        EncodeSet::UserInfo,
+        EncodeSet::FormUrlEncoded,
+        EncodeSet::Unreserved,
+    ];
+
+    proptest! {
+        #[test]
+        fn test_round_trip(src in any::<String>()) {
+            let encoded = super::encode_string(&src);
+            prop_assert!(encoded.as_str().is_ascii());
+
+            let decoded = crate::url_decoder::decode_from_url_query_string(encoded.as_str());
+            prop_assert_eq!(&src, &decoded.unwrap());
+
+            for set in SETS {
+                let encoded = super::encode_with(set, &src);
+
+                let options = crate::url_decoder::DecodeOptions::default()
+                    .with_plus_as_space(set.space_as_plus());
+                let decoded = crate::url_decoder::decode_from_url_query_string_with_options(
+                    encoded.as_str(),
+                    options,
+                );
+
+                prop_assert_eq!(&src, &decoded.unwrap(), "{:?}", set);
+            }
+        }
+
+        // Mostly safe bytes, so the unsafe ones end up in every place of a word
+        #[test]
+        fn test_find_to_encode(
+            src in proptest::collection::vec(prop_oneof![8 => b'a'..=b'z', 1 => any::<u8>()], 0..40)
+        ) {
+            for set in SETS {
+                let expected = src.iter().position(|b| set.must_encode(*b));
+                prop_assert_eq!(super::find_to_encode(set, &src), expected, "{:?}", set);
+            }
+        }
+    }
 }